@@ -0,0 +1,74 @@
+// the partition product of tane, the probe table against the hash set product it replaced.
+// run with `RUSTC_BOOTSTRAP=1 cargo bench --bench partition`
+#![feature(test)]
+extern crate test;
+
+use meta_miner::frontend::partition::{column_partitions, ProbeTable, StrippedPartition};
+use meta_miner::{Attribute, NullModel, Table};
+use std::collections::{HashMap, HashSet};
+use test::Bencher;
+
+const ROWS: usize = 20_000;
+
+// two columns of 100 and 40 values, their product has about 4000 classes
+fn partitions() -> (StrippedPartition, StrippedPartition) {
+    let metadata = vec![Attribute::new(0, "a".to_string()), Attribute::new(1, "b".to_string())];
+    let mut table = Table::new("bench".to_string(), metadata);
+    let mut state = 42u64;
+    for _ in 0..ROWS {
+        state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        table.add_entry(vec![((state >> 33) % 100).to_string(), ((state >> 17) % 40).to_string()]);
+    }
+    let mut partitions = column_partitions(&table, NullModel::Equal);
+    let b = partitions.pop().unwrap();
+    (partitions.pop().unwrap(), b)
+}
+
+fn hash_sets(partition: &StrippedPartition) -> Vec<HashSet<usize>> {
+    partition.clusters().iter().map(|c| c.iter().map(|row| *row as usize).collect()).collect()
+}
+
+// the product before the probe table
+fn hash_product(lhs: &[HashSet<usize>], rhs: &[HashSet<usize>]) -> Vec<HashSet<usize>> {
+    let mut temp_partition = Vec::new();
+    let mut res = Vec::new();
+    let mut idx_check = HashMap::<usize, usize>::new();
+
+    for (index, eq_class) in lhs.iter().enumerate() {
+        temp_partition.push(HashSet::new());
+        for entry in eq_class {
+            idx_check.insert(*entry, index);
+        }
+    }
+
+    for eq_class in rhs.iter() {
+        for entry in eq_class {
+            if let Some(index) = idx_check.get(entry) {
+                temp_partition[*index].insert(*entry);
+            }
+        }
+        for entry in eq_class {
+            if let Some(index) = idx_check.get(entry) {
+                if temp_partition[*index].len() > 1 {
+                    res.push(temp_partition[*index].clone());
+                }
+                temp_partition[*index] = HashSet::new();
+            }
+        }
+    }
+    res
+}
+
+#[bench]
+fn hash_set_product(bencher: &mut Bencher) {
+    let (a, b) = partitions();
+    let (a, b) = (hash_sets(&a), hash_sets(&b));
+    bencher.iter(|| hash_product(&a, &b));
+}
+
+#[bench]
+fn probe_table_product(bencher: &mut Bencher) {
+    let (a, b) = partitions();
+    let mut probe = ProbeTable::new(ROWS);
+    bencher.iter(|| probe.product(&a, &b));
+}
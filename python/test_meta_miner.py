@@ -0,0 +1,55 @@
+# build the module first with `maturin develop`, then run `python python/test_meta_miner.py` or pytest
+import meta_miner
+
+ROWS = [
+    ["1", "Paris", "75"],
+    ["2", "Paris", "75"],
+    ["3", "Lyon", "69"],
+    ["4", "Lyon", "69"],
+]
+COLUMNS = ["id", "city", "zip"]
+
+
+def as_pairs(fds):
+    return {(tuple(fd.lhs), fd.rhs) for fd in fds}
+
+
+def test_rows():
+    fds = meta_miner.discover_fds(ROWS, columns=COLUMNS)
+    assert (("city",), "zip") in as_pairs(fds)
+    assert (("zip",), "city") in as_pairs(fds)
+    assert all(fd.is_exact and fd.error == 0.0 for fd in fds)
+
+
+def test_columns():
+    data = {name: [row[i] for row in ROWS] for i, name in enumerate(COLUMNS)}
+    assert as_pairs(meta_miner.discover_fds(data)) == as_pairs(meta_miner.discover_fds(ROWS, columns=COLUMNS))
+
+
+def test_nulls():
+    rows = [["1", None], ["2", None], ["3", "a"]]
+    equal = as_pairs(meta_miner.discover_fds(rows, null_model="equal"))
+    distinct = as_pairs(meta_miner.discover_fds(rows, null_model="distinct"))
+    assert (("column_1",), "column_0") not in equal
+    assert (("column_1",), "column_0") in distinct
+
+
+def test_approximate():
+    rows = [["a", "x"], ["a", "x"], ["a", "x"], ["a", "y"]]
+    fds = meta_miner.discover_fds(rows, error=0.3)
+    assert any(fd.rhs == "column_1" and fd.error == 0.25 for fd in fds)
+
+
+def test_unknown_algorithm():
+    try:
+        meta_miner.discover_fds(ROWS, algorithm="nope")
+    except ValueError:
+        return
+    assert False
+
+
+if __name__ == "__main__":
+    for name, test in list(globals().items()):
+        if name.startswith("test_"):
+            test()
+    print("ok")
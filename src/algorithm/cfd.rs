@@ -0,0 +1,323 @@
+use crate::algorithm::Algorithm;
+use crate::algorithm::tane::Tane;
+use crate::frontend::partition::{NullModel, Partitions, ProbeTable, StrippedPartition, column_partitions};
+use crate::frontend::table::Table;
+use crate::dependency::cfd::{CFDs, Pattern, PatternTableau, PatternValue};
+use crate::dependency::fd::{Attributes, FDs};
+use crate::dependency::result::Statistics;
+
+use bit_set::BitSet;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::time::Instant;
+
+// the constants of a pattern in the order of its attributes
+type PatternKey = (BitSet, Vec<u32>);
+
+// conditional fds: every frequent combination of constants is a row subset, the classes of the
+// partition products kept above the minimal support. constant cfds pick the dominant value of a column
+// in such a subset, variable cfds are the fds Tane finds in the subset but not on the whole table
+pub struct CfdMiner {
+    min_support: usize,
+    min_confidence: f64,
+    max_lhs: usize,
+    max_condition: usize,
+    null_model: NullModel
+}
+
+impl CfdMiner {
+    pub fn new() -> CfdMiner {
+        CfdMiner { min_support: 10, min_confidence: 1.0, max_lhs: 3, max_condition: 1, null_model: NullModel::Equal }
+    }
+
+    // the rows a pattern has to match, at least 2
+    pub fn min_support(mut self, min_support: usize) -> CfdMiner {
+        self.min_support = min_support.max(2);
+        self
+    }
+
+    pub fn min_confidence(mut self, min_confidence: f64) -> CfdMiner {
+        self.min_confidence = min_confidence;
+        self
+    }
+
+    // the attributes of the lhs of the constant cfds
+    pub fn max_lhs(mut self, max_lhs: usize) -> CfdMiner {
+        self.max_lhs = max_lhs;
+        self
+    }
+
+    // the constant attributes of the variable cfds, 0 mines only constant cfds
+    pub fn max_condition(mut self, max_condition: usize) -> CfdMiner {
+        self.max_condition = max_condition;
+        self
+    }
+
+    // how nulls compare inside the fds of the variable cfds, a null is never a constant of a pattern
+    pub fn null_model(mut self, null_model: NullModel) -> CfdMiner {
+        self.null_model = null_model;
+        self
+    }
+
+    fn frequent(&self, partition: &StrippedPartition) -> StrippedPartition {
+        let clusters = partition.clusters().iter().filter(|c| c.len() >= self.min_support).cloned().collect();
+        StrippedPartition::from_clusters(clusters, partition.row_num())
+    }
+
+    fn approximate_fds(&self, table: &Table) -> FDs {
+        Tane::with_error(1.0 - self.min_confidence).null_model(self.null_model).mine(table)
+    }
+}
+
+impl Default for CfdMiner {
+    fn default() -> CfdMiner {
+        CfdMiner::new()
+    }
+}
+
+fn pattern_key(table: &Table, x: &BitSet, row: u32) -> PatternKey {
+    (x.clone(), x.iter().map(|c| table.code(row as usize, c)).collect())
+}
+
+// the patterns of X without one of its attributes
+fn sub_keys(key: &PatternKey) -> Vec<PatternKey> {
+    let (x, codes) = key;
+    x.iter().enumerate().map(|(i, b)| {
+        let mut y = x.clone();
+        y.remove(b);
+        let mut codes = codes.clone();
+        codes.remove(i);
+        (y, codes)
+    }).collect()
+}
+
+// the dominant non null value of a column among the rows
+fn dominant(table: &Table, rows: &[u32], col: usize) -> Option<(u32, usize)> {
+    let mut counts = HashMap::<u32, usize>::new();
+    for row in rows.iter().map(|row| *row as usize).filter(|row| !table.is_null(*row, col)) {
+        *counts.entry(table.code(row, col)).or_insert(0) += 1;
+    }
+    counts.into_iter().max_by(|(c1, n1), (c2, n2)| n1.cmp(n2).then(c2.cmp(c1)))
+}
+
+impl Algorithm for CfdMiner {
+    type Result = CFDs;
+
+    fn execute(&mut self, table: &Table) -> CFDs {
+        let start = Instant::now();
+        let mut statistics = Statistics::default();
+        let mut res = CFDs::new(table).with_null_model(self.null_model);
+
+        let attri_num = table.attributes.len();
+        let row_num = table.row_num();
+        let columns: Vec<StrippedPartition> = column_partitions(table, NullModel::Distinct).iter().map(|p| self.frequent(p)).collect();
+
+        // (|lhs|, lhs, A) -> patterns, so the tableaux come out in a stable order
+        let mut tableaux = BTreeMap::<(usize, Vec<usize>, usize), Vec<Pattern>>::new();
+
+        // constant cfds, a rule holding exactly for a sub pattern is implied for all the larger ones.
+        // an approximate rule implies nothing, a larger pattern may hold it with another confidence
+        let mut implied = HashMap::<PatternKey, HashSet<(usize, u32)>>::new();
+        let mut levels: Vec<Vec<(BitSet, StrippedPartition)>> = Vec::new();
+        let mut level = vec![(BitSet::new(), self.frequent(&StrippedPartition::new(row_num)))];
+        let mut probe = ProbeTable::new(row_num);
+
+        while !level.is_empty() {
+            let mut candidates = 0;
+            let mut found = 0;
+            // the levels deeper than max_lhs are only built for the conditions
+            let constants = if levels.len() <= self.max_lhs { level.as_slice() } else { &[] };
+            for (x, partition) in constants.iter() {
+                for cluster in partition.clusters() {
+                    candidates += 1;
+                    let key = pattern_key(table, x, cluster[0]);
+                    let mut exact: HashSet<(usize, u32)> = sub_keys(&key).iter()
+                        .filter_map(|sub| implied.get(sub))
+                        .flatten()
+                        .cloned()
+                        .collect();
+
+                    for a in (0..attri_num).filter(|a| !x.contains(*a)) {
+                        let (code, count) = match dominant(table, cluster, a) {
+                            Some(dominant) => dominant,
+                            None => continue
+                        };
+                        let confidence = count as f64 / cluster.len() as f64;
+                        if confidence < self.min_confidence || exact.contains(&(a, code)) {
+                            continue;
+                        }
+                        if count == cluster.len() {
+                            exact.insert((a, code));
+                        }
+
+                        found += 1;
+                        let row = cluster[0] as usize;
+                        tableaux.entry((x.len(), x.iter().collect(), a)).or_default().push(Pattern {
+                            lhs: x.iter().map(|c| PatternValue::Constant(table.value(row, c).to_string())).collect(),
+                            rhs: PatternValue::Constant(table.value(row_of(cluster, table, a, code), a).to_string()),
+                            support: cluster.len(),
+                            confidence
+                        });
+                    }
+                    implied.insert(key, exact);
+                }
+            }
+            if levels.len() <= self.max_lhs {
+                statistics.add_level(candidates, found);
+            }
+
+            let size = levels.len();
+            levels.push(level);
+            if size == self.max_lhs.max(self.max_condition) {
+                break;
+            }
+
+            // every set is extended by the attributes after its largest one
+            let mut next = Vec::new();
+            for (x, partition) in levels[size].iter() {
+                let from = x.iter().max().map_or(0, |max| max + 1);
+                for (b, column) in columns.iter().enumerate().skip(from) {
+                    let product = self.frequent(&probe.product(partition, column));
+                    if !product.clusters().is_empty() {
+                        let mut y = x.clone();
+                        y.insert(b);
+                        next.push((y, product));
+                    }
+                }
+            }
+            level = next;
+        }
+
+        if self.max_condition > 0 {
+            let global = self.approximate_fds(table);
+            let mut variable = HashMap::<PatternKey, Vec<(BitSet, usize)>>::new();
+
+            for (x, partition) in levels.iter().skip(1).take(self.max_condition).flatten() {
+                for cluster in partition.clusters() {
+                    let key = pattern_key(table, x, cluster[0]);
+                    let sub_table = table.sub_table(&cluster.iter().map(|row| *row as usize).collect());
+                    let sub_partitions = Partitions::with_null_model(&sub_table, self.null_model);
+                    let mut found = Vec::new();
+
+                    for fd in self.approximate_fds(&sub_table).iter() {
+                        let (y, a) = (fd.determinant_set(), fd.dependant_rank());
+                        if y.is_empty() || x.contains(a) || !y.is_disjoint(x) {
+                            continue;
+                        }
+                        if has_generalization(global.iter().map(|fd| (fd.determinant_set(), fd.dependant_rank())), &y, a)
+                            || sub_keys(&key).iter().filter_map(|sub| variable.get(sub)).any(|fds| has_generalization(fds.iter().cloned(), &y, a)) {
+                            continue;
+                        }
+
+                        // on a key of the subset the fd holds without saying anything
+                        let y_partition = y.iter().fold(StrippedPartition::new(sub_table.row_num()), |p, c| {
+                            probe.product(&p, sub_partitions.0.get(&table.attributes[c]).unwrap())
+                        });
+                        if y_partition.is_unique() {
+                            continue;
+                        }
+
+                        let mut lhs = x.clone();
+                        lhs.union_with(&y);
+                        let row = cluster[0] as usize;
+                        tableaux.entry((lhs.len(), lhs.iter().collect(), a)).or_default().push(Pattern {
+                            lhs: lhs.iter().map(|c| if x.contains(c) {
+                                PatternValue::Constant(table.value(row, c).to_string())
+                            } else {
+                                PatternValue::Wildcard
+                            }).collect(),
+                            rhs: PatternValue::Wildcard,
+                            support: cluster.len(),
+                            confidence: 1.0 - fd.error()
+                        });
+                        found.push((y, a));
+                    }
+                    log::debug!("{} variable cfds on {} rows of the pattern {:?}", found.len(), cluster.len(), key);
+                    variable.insert(key, found);
+                }
+            }
+        }
+
+        for ((_, lhs, a), mut patterns) in tableaux {
+            patterns.sort_by(|p1, p2| p2.support.cmp(&p1.support).then(p1.lhs.cmp(&p2.lhs)));
+            res.add(PatternTableau {
+                determinant: Attributes(lhs.iter().map(|c| table.attributes[*c].clone()).collect()),
+                dependant: table.attributes[a].clone(),
+                patterns
+            });
+        }
+
+        statistics.elapsed = start.elapsed();
+        res.set_statistics(statistics);
+        res
+    }
+}
+
+// a row of the cluster holding the code in the column
+fn row_of(cluster: &[u32], table: &Table, col: usize, code: u32) -> usize {
+    cluster.iter().map(|row| *row as usize).find(|row| !table.is_null(*row, col) && table.code(*row, col) == code).unwrap()
+}
+
+// whether some Z -> A with Z ⊆ Y is among the fds
+fn has_generalization(mut fds: impl Iterator<Item = (BitSet, usize)>, y: &BitSet, a: usize) -> bool {
+    fds.any(|(z, b)| b == a && z.is_subset(y))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dependency::result::AlgorithmResult;
+    use crate::frontend::table::Attribute;
+
+    fn addresses() -> Table {
+        let metadata = ["country", "zip", "city"].iter().enumerate().map(|(rank, name)| Attribute::new(rank, name.to_string())).collect();
+        let mut table = Table::new("addresses".to_string(), metadata);
+        let rows = [["UK", "EH4", "Edinburgh"], ["UK", "EH4", "Edinburgh"], ["UK", "W1", "London"], ["UK", "W1", "London"],
+                    ["US", "10001", "New York"], ["US", "10001", "Newark"], ["US", "02139", "Boston"], ["US", "02139", "Boston"]];
+        for row in rows {
+            table.add_entry(row.iter().map(|v| v.to_string()).collect());
+        }
+        table
+    }
+
+    fn rules(res: &CFDs) -> Vec<String> {
+        res.patterns().map(|(tableau, pattern)| tableau.rule(pattern)).collect()
+    }
+
+    #[test]
+    fn mines_constant_and_variable_cfds() {
+        let res = CfdMiner::new().min_support(2).execute(&addresses());
+        let rules = rules(&res);
+
+        assert!(rules.contains(&"[country='UK', zip] -> city".to_string()));
+        assert!(rules.contains(&"[zip='EH4'] -> city='Edinburgh'".to_string()));
+        assert!(rules.contains(&"[city='Boston'] -> country='US'".to_string()));
+        // zip -> city is broken by the US rows
+        assert!(!rules.iter().any(|rule| rule.starts_with("[country='US', zip]")));
+        // implied by [zip='EH4'] -> city='Edinburgh'
+        assert!(!rules.contains(&"[country='UK', zip='EH4'] -> city='Edinburgh'".to_string()));
+        assert!(res.dependencies().iter().flat_map(|t| t.patterns.iter()).all(|p| p.confidence == 1.0));
+    }
+
+    #[test]
+    fn reports_the_confidence_of_approximate_patterns() {
+        let res = CfdMiner::new().min_support(2).min_confidence(0.5).max_condition(0).execute(&addresses());
+        let newark = res.patterns().find(|(tableau, pattern)| tableau.rule(pattern) == "[zip='10001'] -> city='New York'").unwrap().1;
+        assert_eq!(newark.support, 2);
+        assert_eq!(newark.confidence, 0.5);
+    }
+
+    #[test]
+    fn approximate_rules_imply_nothing() {
+        let rows: Vec<Vec<String>> = [["x", "1", "k"], ["x", "1", "k"], ["x", "2", "k"], ["x", "2", "m"], ["y", "1", "m"], ["y", "1", "m"], ["y", "1", "m"]]
+            .iter().map(|row| row.iter().map(|v| v.to_string()).collect()).collect();
+        let table = crate::testing::table("t", &["a", "b", "c"], &rows);
+        let res = CfdMiner::new().min_support(2).min_confidence(0.7).max_condition(0).execute(&table);
+        let rules = rules(&res);
+
+        assert!(rules.contains(&"[a='x'] -> c='k'".to_string()));
+        // exact on the larger pattern, [b='1'] -> c='m' is below the confidence
+        assert!(rules.contains(&"[a='x', b='1'] -> c='k'".to_string()));
+        // implied by [a='y'] -> c='m'
+        assert!(!rules.contains(&"[a='y', b='1'] -> c='m'".to_string()));
+    }
+}
@@ -1,22 +1,280 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet, VecDeque};
 
-use crate::frontend::table::{*, self};
+use crate::frontend::table::*;
+#[cfg(feature = "python")]
 use pyo3::prelude::*;
-use pyo3::types::{IntoPyDict, PySet, PyList, PyString, PyTuple};
+#[cfg(feature = "python")]
+use pyo3::types::{PyList, PyString, PyTuple};
+#[cfg(feature = "python")]
 use serde::{Deserialize,Serialize};
+#[cfg(feature = "python")]
 #[derive(Deserialize, Serialize, Debug)]
 struct Nodes(Vec<HashSet<usize>>);
 
+// categorical columns with more distinct values than this are frequency encoded instead of one-hot
+const MAX_ONE_HOT: usize = 32;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Linkage {
+    Ward,
+    Average,
+    Complete
+}
+
+impl Linkage {
+    pub fn from_name(name: &str) -> Option<Linkage> {
+        match name {
+            "ward" => Some(Linkage::Ward),
+            "average" => Some(Linkage::Average),
+            "complete" => Some(Linkage::Complete),
+            _ => None
+        }
+    }
+}
+
+// one step of the dendrogram, in the layout of scipy's linkage matrix
+#[derive(Clone, Debug)]
+pub struct Merge {
+    pub left: usize,
+    pub right: usize,
+    pub distance: f64,
+    pub size: usize
+}
+
 pub fn clustering(table: &Table) -> Vec<HashSet<usize>> {
+    clustering_with(table, Linkage::Ward, 5)
+}
+
+pub fn clustering_with(table: &Table, method: Linkage, cnt: usize) -> Vec<HashSet<usize>> {
+    let points = features(table);
+    let merges = linkage(&points, method);
+    cut(&merges, points.len(), cnt)
+}
+
+pub fn features(table: &Table) -> Vec<Vec<f64>> {
+    let mut points = vec![Vec::new(); table.row_num()];
+
+    for column in table.columns.iter() {
+        // every distinct value is parsed once through the dictionary, nan and inf make the column categorical
+        let dictionary = &column.dictionary;
+        let numeric: Option<Vec<f64>> = (0..dictionary.len() as u32).map(|code| {
+            let s = dictionary.decode(code).trim();
+            if dictionary.is_null(code) || s.is_empty() { Some(0.0) } else { s.parse::<f64>().ok().filter(|x| x.is_finite()) }
+        }).collect();
+
+        if let Some(numeric) = numeric {
+            let present = || column.codes.iter().map(|code| numeric[*code as usize]);
+            let min = present().fold(f64::INFINITY, f64::min);
+            let max = present().fold(f64::NEG_INFINITY, f64::max);
+            let range = if max > min { max - min } else { 1.0 };
+            for (point, x) in points.iter_mut().zip(present()) {
+                point.push((x - min) / range);
+            }
+            continue;
+        }
+
+        let mut frequency = HashMap::<u32, usize>::new();
+        for code in column.codes.iter() {
+            *frequency.entry(*code).or_insert(0) += 1;
+        }
+
+        if frequency.len() <= MAX_ONE_HOT {
+            let mut distinct: Vec<u32> = frequency.keys().cloned().collect();
+            distinct.sort_by(|a, b| dictionary.decode(*a).cmp(dictionary.decode(*b)));
+            let position: HashMap<u32, usize> = distinct.into_iter().enumerate().map(|(i, v)| (v, i)).collect();
+            for (point, code) in points.iter_mut().zip(column.codes.iter()) {
+                let mut one_hot = vec![0.0; position.len()];
+                one_hot[position[code]] = 1.0;
+                point.extend(one_hot);
+            }
+        } else {
+            let total = column.codes.len() as f64;
+            for (point, code) in points.iter_mut().zip(column.codes.iter()) {
+                point.push(frequency[code] as f64 / total);
+            }
+        }
+    }
+
+    points
+}
+
+// agglomerative clustering with the nearest-neighbor chain algorithm and Lance-Williams updates
+pub fn linkage(points: &[Vec<f64>], method: Linkage) -> Vec<Merge> {
+    let n = points.len();
+    if n < 2 {
+        return Vec::new();
+    }
+
+    let index = |i: usize, j: usize| -> usize {
+        let (i, j) = if i < j { (i, j) } else { (j, i) };
+        n * i - i * (i + 1) / 2 + (j - i - 1)
+    };
+
+    let mut dist = vec![0.0; n * (n - 1) / 2];
+    for i in 0..n {
+        for j in (i + 1)..n {
+            let d: f64 = points[i].iter().zip(points[j].iter()).map(|(a, b)| (a - b) * (a - b)).sum();
+            dist[index(i, j)] = d.sqrt();
+        }
+    }
+
+    let mut size = vec![1usize; n];
+    let mut active = vec![true; n];
+    let mut chain: Vec<usize> = Vec::new();
+    let mut raw = Vec::new();
+
+    for _ in 0..(n - 1) {
+        if chain.is_empty() {
+            chain.push((0..n).find(|i| active[*i]).unwrap());
+        }
+
+        let (x, y) = loop {
+            let x = *chain.last().unwrap();
+            let prev = if chain.len() >= 2 { Some(chain[chain.len() - 2]) } else { None };
+
+            let mut y = prev.unwrap_or(usize::MAX);
+            let mut best = prev.map(|p| dist[index(x, p)]).unwrap_or(f64::INFINITY);
+            for k in 0..n {
+                if k != x && active[k] && dist[index(x, k)] < best {
+                    best = dist[index(x, k)];
+                    y = k;
+                }
+            }
+
+            if Some(y) == prev {
+                chain.pop();
+                chain.pop();
+                break (x, y);
+            }
+            chain.push(y);
+        };
+
+        let d_xy = dist[index(x, y)];
+        let (size_x, size_y) = (size[x] as f64, size[y] as f64);
+        for k in 0..n {
+            if !active[k] || k == x || k == y {
+                continue;
+            }
+            let (d_xk, d_yk) = (dist[index(x, k)], dist[index(y, k)]);
+            let size_k = size[k] as f64;
+            dist[index(y, k)] = match method {
+                Linkage::Ward => {
+                    let total = size_x + size_y + size_k;
+                    (((size_x + size_k) * d_xk * d_xk + (size_y + size_k) * d_yk * d_yk - size_k * d_xy * d_xy) / total).sqrt()
+                }
+                Linkage::Average => (size_x * d_xk + size_y * d_yk) / (size_x + size_y),
+                Linkage::Complete => d_xk.max(d_yk)
+            };
+        }
+
+        active[x] = false;
+        size[y] += size[x];
+        raw.push((x, y, d_xy));
+    }
+
+    // the chain finds merges out of order, relabel them the way scipy does
+    raw.sort_by(|a, b| a.2.total_cmp(&b.2));
+
+    let mut parent: Vec<usize> = (0..(2 * n - 1)).collect();
+    let mut cluster_size = vec![1usize; 2 * n - 1];
+    fn find(parent: &mut [usize], x: usize) -> usize {
+        let mut root = x;
+        while parent[root] != root {
+            root = parent[root];
+        }
+        let mut x = x;
+        while parent[x] != root {
+            let next = parent[x];
+            parent[x] = root;
+            x = next;
+        }
+        root
+    }
+
+    raw.into_iter().enumerate().map(|(i, (x, y, distance))| {
+        let (a, b) = (find(&mut parent, x), find(&mut parent, y));
+        let node = n + i;
+        parent[a] = node;
+        parent[b] = node;
+        cluster_size[node] = cluster_size[a] + cluster_size[b];
+        Merge { left: a.min(b), right: a.max(b), distance, size: cluster_size[node] }
+    }).collect()
+}
+
+// pick the cnt nodes closest to the root among the widest merges, in breadth first order
+pub fn cut(merges: &[Merge], n: usize, cnt: usize) -> Vec<HashSet<usize>> {
+    if n == 0 {
+        return Vec::new();
+    }
+
+    let root = n + merges.len() - 1;
+    let children = |node: usize| -> Option<(usize, usize)> {
+        if node < n { None } else { Some((merges[node - n].left, merges[node - n].right)) }
+    };
+
+    let rows_of = |node: usize| -> HashSet<usize> {
+        let mut rows = HashSet::new();
+        let mut stack = vec![node];
+        while let Some(top) = stack.pop() {
+            if let Some((left, right)) = children(top) {
+                stack.push(left);
+                stack.push(right);
+            } else {
+                rows.insert(top);
+            }
+        }
+        rows
+    };
+
+    let mut nodes: Vec<usize> = (0..=root).collect();
+    nodes.sort_by(|a, b| {
+        let da = if *a < n { 0.0 } else { merges[*a - n].distance };
+        let db = if *b < n { 0.0 } else { merges[*b - n].distance };
+        db.total_cmp(&da)
+    });
+
+    let mut save_nodes = HashSet::new();
+    for node in nodes {
+        save_nodes.insert(node);
+        if let Some((left, right)) = children(node) {
+            save_nodes.insert(left);
+            save_nodes.insert(right);
+        }
+        if save_nodes.len() >= cnt {
+            break;
+        }
+    }
+
+    let mut res = Vec::new();
+    let mut queue = VecDeque::new();
+    queue.push_back(root);
+    while let Some(top) = queue.pop_front() {
+        if save_nodes.contains(&top) {
+            res.push(rows_of(top));
+        }
+        if res.len() >= cnt {
+            break;
+        }
+        if let Some((left, right)) = children(top) {
+            queue.push_back(left);
+            queue.push_back(right);
+        }
+    }
+
+    res
+}
+
+#[cfg(feature = "python")]
+pub fn python_clustering(table: &Table) -> Vec<HashSet<usize>> {
 
     let mut instance = Vec::new();
-    for item in table.entries.iter() {
-        instance.push(item.data.clone());
+    for row in 0..table.row_num() {
+        instance.push(table.row(row).into_iter().map(|s| s.to_string()).collect::<Vec<String>>());
     }
 
     let py_cluster = include_str!("../../python/cluster.py");
     let from_python = Python::with_gil(|py| -> PyResult<Py<PyAny>> {
-        
+
         let cluster: Py<PyAny> = PyModule::from_code(py, py_cluster, "", "")?
             .getattr("clustering")?.into();
 
@@ -28,7 +286,6 @@ pub fn clustering(table: &Table) -> Vec<HashSet<usize>> {
         }).collect();
 
         let table = PyList::new(py, table);
-        // println!("{}", table);
 
         let args = PyTuple::new(py, vec![table]);
         cluster.call1(py, args)
@@ -39,4 +296,56 @@ pub fn clustering(table: &Table) -> Vec<HashSet<usize>> {
     let s = s.replace("}", "]");
     let nodes: Nodes = serde_json::from_str(s.as_str()).unwrap();
     nodes.0
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    // the rows of one column
+    fn column(values: &[&str]) -> Table {
+        let rows: Vec<Vec<String>> = values.iter().map(|v| vec![v.to_string()]).collect();
+        testing::table("points", &["x"], &rows)
+    }
+
+    fn dendrogram(merges: &[Merge]) -> Vec<(usize, usize, f64, usize)> {
+        merges.iter().map(|m| (m.left, m.right, (m.distance * 1e4).round() / 1e4, m.size)).collect()
+    }
+
+    #[test]
+    fn linkages_match_scipy() {
+        // scipy.cluster.hierarchy.linkage([[0], [1], [3], [7]], method)
+        let points = vec![vec![0.0], vec![1.0], vec![3.0], vec![7.0]];
+        assert_eq!(dendrogram(&linkage(&points, Linkage::Complete)), vec![(0, 1, 1.0, 2), (2, 4, 3.0, 3), (3, 5, 7.0, 4)]);
+        assert_eq!(dendrogram(&linkage(&points, Linkage::Average)), vec![(0, 1, 1.0, 2), (2, 4, 2.5, 3), (3, 5, 5.6667, 4)]);
+        assert_eq!(dendrogram(&linkage(&points, Linkage::Ward)), vec![(0, 1, 1.0, 2), (2, 4, 2.8868, 3), (3, 5, 6.9402, 4)]);
+
+        // two groups far apart, the last merge joins them
+        let points = vec![vec![0.0, 0.0], vec![10.0, 10.0], vec![0.0, 1.0], vec![10.0, 11.0]];
+        assert_eq!(dendrogram(&linkage(&points, Linkage::Complete)), vec![(0, 2, 1.0, 2), (1, 3, 1.0, 2), (4, 5, 14.8661, 4)]);
+    }
+
+    #[test]
+    fn cuts_the_widest_merges() {
+        let merges = linkage(&[vec![0.0], vec![1.0], vec![3.0], vec![7.0]], Linkage::Complete);
+        // the root is the first node, the whole table
+        let mut clusters: Vec<Vec<usize>> = cut(&merges, 4, 3).into_iter().map(|c| { let mut c: Vec<usize> = c.into_iter().collect(); c.sort(); c }).collect();
+        clusters.sort();
+        assert_eq!(clusters, vec![vec![0, 1, 2], vec![0, 1, 2, 3], vec![3]]);
+        assert!(cut(&[], 0, 3).is_empty());
+    }
+
+    #[test]
+    fn non_finite_values_are_categories() {
+        let table = column(&["1", "NaN", "inf", "2", "-inf", "1"]);
+        let points = features(&table);
+        assert!(points.iter().flatten().all(|x| x.is_finite()));
+        // one-hot over the five distinct values
+        assert_eq!(points[0].len(), 5);
+
+        let clusters = clustering_with(&table, Linkage::Ward, 3);
+        let rows: HashSet<usize> = clusters.iter().flatten().cloned().collect();
+        assert_eq!(rows, (0..6).collect());
+    }
+}
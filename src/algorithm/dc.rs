@@ -0,0 +1,548 @@
+use crate::algorithm::Algorithm;
+use crate::frontend::partition::NullModel;
+use crate::frontend::table::Table;
+use crate::frontend::types::ColumnType;
+use crate::dependency::dc::{DCs, DenialConstraint, Operator, Predicate};
+use crate::dependency::result::Statistics;
+
+use bit_set::BitSet;
+use rayon::prelude::*;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::time::Instant;
+
+// denial constraints the fastdc way: the evidence set of a row pair is every predicate it satisfies,
+// and ¬(P1 ∧ ... ∧ Pk) holds when each evidence set misses one of the Pi. the minimal dcs are then the
+// minimal covers of the predicates missing from the evidence sets, found depth first
+pub struct DcMiner {
+    max_error: f64,
+    max_predicates: usize,
+    min_overlap: f64,
+    parallel: bool,
+    null_model: NullModel,
+    column_types: Option<Vec<ColumnType>>
+}
+
+impl DcMiner {
+    pub fn new() -> DcMiner {
+        DcMiner { max_error: 0.0, max_predicates: 4, min_overlap: 0.3, parallel: false, null_model: NullModel::Equal, column_types: None }
+    }
+
+    // approximate mode: the share of the ordered row pairs a dc may be violated by
+    pub fn max_error(mut self, max_error: f64) -> DcMiner {
+        self.max_error = max_error;
+        self
+    }
+
+    pub fn max_predicates(mut self, max_predicates: usize) -> DcMiner {
+        self.max_predicates = max_predicates;
+        self
+    }
+
+    // two columns of the same type are only compared with each other when this share of the values
+    // of the smaller one also appears in the other
+    pub fn min_overlap(mut self, min_overlap: f64) -> DcMiner {
+        self.min_overlap = min_overlap;
+        self
+    }
+
+    // build the evidence sets on all the cores
+    pub fn parallel(mut self) -> DcMiner {
+        self.parallel = true;
+        self
+    }
+
+    pub fn null_model(mut self, null_model: NullModel) -> DcMiner {
+        self.null_model = null_model;
+        self
+    }
+
+    pub fn column_types(mut self, column_types: Vec<ColumnType>) -> DcMiner {
+        self.column_types = Some(column_types);
+        self
+    }
+}
+
+impl Default for DcMiner {
+    fn default() -> DcMiner {
+        DcMiner::new()
+    }
+}
+
+// the predicates over one pair of operands, at most one of them is part of a dc
+// since any satisfiable combination of them is a single operator
+struct Group {
+    left: usize,
+    right: usize,
+    same_tuple: bool,
+    predicates: Vec<(Operator, usize)>
+}
+
+struct PredicateSpace {
+    predicates: Vec<(usize, Operator, usize, bool)>,
+    groups: Vec<Group>,
+    group_of: Vec<usize>,
+    // s.B op t.A read the other way round, so a dc and the one with t and s swapped are found once
+    mirror: Vec<Option<usize>>
+}
+
+// the values of the columns of one type ranked together from 1, so different columns compare. 0 is null
+fn joint_ranks(table: &Table, column_types: &[ColumnType]) -> Vec<Vec<u32>> {
+    let mut ranks = vec![Vec::new(); column_types.len()];
+    for column_type in [ColumnType::Numeric, ColumnType::Date, ColumnType::String] {
+        let columns: Vec<usize> = (0..column_types.len()).filter(|c| column_types[*c] == column_type).collect();
+        let mut values: Vec<(usize, u32)> = columns.iter()
+            .flat_map(|c| {
+                let dictionary = &table.columns[*c].dictionary;
+                (0..dictionary.len() as u32).filter(move |code| !dictionary.is_null(*code)).map(move |code| (*c, code))
+            })
+            .collect();
+        let value = |(c, code): &(usize, u32)| table.columns[*c].dictionary.decode(*code);
+        values.sort_by(|x, y| column_type.compare(value(x), value(y)));
+
+        let mut code_ranks: HashMap<usize, Vec<u32>> = columns.iter().map(|c| (*c, vec![0; table.columns[*c].dictionary.len()])).collect();
+        let mut rank = 0;
+        for (i, entry) in values.iter().enumerate() {
+            if i == 0 || column_type.compare(value(&values[i - 1]), value(entry)).is_ne() {
+                rank += 1;
+            }
+            code_ranks.get_mut(&entry.0).unwrap()[entry.1 as usize] = rank;
+        }
+        for c in columns {
+            ranks[c] = table.columns[c].codes.iter().map(|code| code_ranks[&c][*code as usize]).collect();
+        }
+    }
+    ranks
+}
+
+impl PredicateSpace {
+    fn new(ranks: &[Vec<u32>], column_types: &[ColumnType], min_overlap: f64) -> PredicateSpace {
+        let attri_num = column_types.len();
+        let values: Vec<HashSet<u32>> = ranks.iter().map(|r| r.iter().filter(|rank| **rank != 0).cloned().collect()).collect();
+        let comparable = |a: usize, b: usize| {
+            let smaller = values[a].len().min(values[b].len());
+            a == b || (column_types[a] == column_types[b] && smaller > 0
+                && values[a].intersection(&values[b]).count() as f64 >= min_overlap * smaller as f64)
+        };
+
+        let mut space = PredicateSpace { predicates: Vec::new(), groups: Vec::new(), group_of: Vec::new(), mirror: Vec::new() };
+        for same_tuple in [false, true] {
+            for (a, column_type) in column_types.iter().enumerate() {
+                for b in 0..attri_num {
+                    if (same_tuple && a >= b) || !comparable(a, b) {
+                        continue;
+                    }
+                    let mut group = Group { left: a, right: b, same_tuple, predicates: Vec::new() };
+                    for operator in Operator::ALL.into_iter().filter(|op| *column_type != ColumnType::String || !op.is_order()) {
+                        group.predicates.push((operator, space.predicates.len()));
+                        space.predicates.push((a, operator, b, same_tuple));
+                        space.group_of.push(space.groups.len());
+                    }
+                    space.groups.push(group);
+                }
+            }
+        }
+
+        let index: HashMap<(usize, Operator, usize, bool), usize> = space.predicates.iter().cloned().enumerate().map(|(i, p)| (p, i)).collect();
+        space.mirror = space.predicates.iter()
+            .map(|(a, op, b, same_tuple)| if *same_tuple { None } else { index.get(&(*b, op.converse(), *a, false)).cloned() })
+            .collect();
+        space
+    }
+
+    fn mirror_of(&self, dc: &[usize]) -> Option<Vec<usize>> {
+        let mut mirror = dc.iter().map(|p| self.mirror[*p]).collect::<Option<Vec<usize>>>()?;
+        mirror.sort();
+        Some(mirror)
+    }
+
+    // ¬(stronger) implies ¬(dc) when each predicate of stronger follows from one of dc
+    fn implies(&self, stronger: &[usize], dc: &[usize]) -> bool {
+        stronger.iter().all(|q| dc.iter().any(|p| self.group_of[*p] == self.group_of[*q] && self.predicates[*p].1.implies(self.predicates[*q].1)))
+    }
+
+    fn groups(&self, dc: &[usize]) -> Vec<usize> {
+        let mut groups: Vec<usize> = dc.iter().map(|p| self.group_of[*p]).collect();
+        groups.sort();
+        groups
+    }
+
+    // the predicates satisfied by the rows t and s
+    fn evidence(&self, ranks: &[Vec<u32>], t: usize, s: usize, null_model: NullModel) -> BitSet {
+        let mut evidence = BitSet::with_capacity(self.predicates.len());
+        for group in self.groups.iter() {
+            let x = ranks[group.left][t];
+            let y = ranks[group.right][if group.same_tuple { t } else { s }];
+            let relation = match (x, y) {
+                (0, 0) if null_model == NullModel::Equal => Some(Ordering::Equal),
+                (0, _) | (_, 0) => None,
+                _ => Some(x.cmp(&y))
+            };
+            for (operator, predicate) in group.predicates.iter() {
+                let satisfied = match (operator, relation) {
+                    (Operator::NotEqual, None) => true,
+                    (_, None) => false,
+                    (Operator::Equal, Some(r)) => r.is_eq(),
+                    (Operator::NotEqual, Some(r)) => r.is_ne(),
+                    (Operator::Less, Some(r)) => r.is_lt(),
+                    (Operator::Greater, Some(r)) => r.is_gt(),
+                    (Operator::LessEqual, Some(r)) => r.is_le(),
+                    (Operator::GreaterEqual, Some(r)) => r.is_ge()
+                };
+                if satisfied {
+                    evidence.insert(*predicate);
+                }
+            }
+        }
+        evidence
+    }
+}
+
+fn evidence_of(space: &PredicateSpace, ranks: &[Vec<u32>], t: usize, null_model: NullModel) -> HashMap<BitSet, usize> {
+    let mut evidence = HashMap::new();
+    for s in (0..ranks[0].len()).filter(|s| *s != t) {
+        *evidence.entry(space.evidence(ranks, t, s, null_model)).or_insert(0) += 1;
+    }
+    evidence
+}
+
+struct Search<'a> {
+    // the predicates missing from every evidence set, with the row pairs of the evidence set
+    sets: &'a [(BitSet, usize)],
+    group_of: &'a [usize],
+    budget: usize,
+    max_predicates: usize,
+    found: Vec<Vec<usize>>,
+    // the paths searched of every size
+    levels: Vec<usize>
+}
+
+impl<'a> Search<'a> {
+    // every predicate is the only one of the path missing from enough evidence to be needed
+    fn is_minimal(&self, path: &[usize], uncovered: usize) -> bool {
+        let mut unique = vec![0; path.len()];
+        for (set, pairs) in self.sets.iter() {
+            let mut hits = path.iter().enumerate().filter(|(_, p)| set.contains(**p));
+            if let (Some((i, _)), None) = (hits.next(), hits.next()) {
+                unique[i] += pairs;
+            }
+        }
+        unique.iter().all(|u| u + uncovered > self.budget)
+    }
+
+    // the candidates missing from the most remaining evidence come first
+    fn ordering(&self, candidates: impl Iterator<Item = usize>, remaining: &[usize]) -> Vec<usize> {
+        let mut counted: Vec<(usize, usize)> = candidates
+            .map(|p| (p, remaining.iter().filter(|set| self.sets[**set].0.contains(p)).map(|set| self.sets[*set].1).sum()))
+            .filter(|(_, count)| *count > 0)
+            .collect();
+        counted.sort_by(|(p1, c1), (p2, c2)| c2.cmp(c1).then(p1.cmp(p2)));
+        counted.into_iter().map(|(p, _)| p).collect()
+    }
+
+    // a predicate is only followed by the ones after it in the ordering, and a path that is not
+    // minimal any more is left since more predicates never make it minimal again
+    fn run(&mut self, path: &mut Vec<usize>, remaining: &[usize], candidates: &[usize]) {
+        let uncovered: usize = remaining.iter().map(|set| self.sets[*set].1).sum();
+        if !self.is_minimal(path, uncovered) {
+            return;
+        }
+        if !path.is_empty() && uncovered <= self.budget {
+            self.found.push(path.clone());
+            return;
+        }
+        if path.len() == self.max_predicates {
+            return;
+        }
+
+        for (i, p) in candidates.iter().enumerate() {
+            if path.iter().any(|q| self.group_of[*q] == self.group_of[*p]) {
+                continue;
+            }
+            let next: Vec<usize> = remaining.iter().filter(|set| !self.sets[**set].0.contains(*p)).cloned().collect();
+            let next_candidates = self.ordering(candidates[i + 1..].iter().cloned(), &next);
+
+            // the later predicates leave too many pairs uncovered
+            let reachable: BitSet = next_candidates.iter().cloned().collect();
+            let unreachable: usize = next.iter().filter(|set| self.sets[**set].0.is_disjoint(&reachable)).map(|set| self.sets[*set].1).sum();
+            if unreachable > self.budget {
+                continue;
+            }
+
+            self.levels[path.len()] += 1;
+            path.push(*p);
+            self.run(path, &next, &next_candidates);
+            path.pop();
+        }
+    }
+}
+
+impl Algorithm for DcMiner {
+    type Result = DCs;
+
+    fn execute(&mut self, table: &Table) -> DCs {
+        let start = Instant::now();
+        let mut statistics = Statistics::default();
+
+        let attri_num = table.attributes.len();
+        let row_num = table.row_num();
+        let column_types = match self.column_types.clone() {
+            Some(types) => types,
+            None => (0..attri_num).map(|col| ColumnType::infer(table, col)).collect()
+        };
+        let mut res = DCs::new(table, column_types.clone()).with_null_model(self.null_model);
+        // without a pair of rows every predicate would be a dc
+        if row_num < 2 || attri_num == 0 {
+            statistics.elapsed = start.elapsed();
+            res.set_statistics(statistics);
+            return res;
+        }
+
+        let ranks = joint_ranks(table, &column_types);
+        let space = PredicateSpace::new(&ranks, &column_types, self.min_overlap);
+        log::debug!("{} predicates in {} groups", space.predicates.len(), space.groups.len());
+
+        let null_model = self.null_model;
+        let evidence: HashMap<BitSet, usize> = if self.parallel {
+            (0..row_num).into_par_iter()
+                .map(|t| evidence_of(&space, &ranks, t, null_model))
+                .reduce(HashMap::new, |mut a, b| {
+                    b.into_iter().for_each(|(e, pairs)| *a.entry(e).or_insert(0) += pairs);
+                    a
+                })
+        } else {
+            let mut evidence = HashMap::new();
+            for t in 0..row_num {
+                evidence_of(&space, &ranks, t, null_model).into_iter().for_each(|(e, pairs)| *evidence.entry(e).or_insert(0) += pairs);
+            }
+            evidence
+        };
+        log::debug!("{} distinct evidence sets of {} row pairs", evidence.len(), row_num * (row_num - 1));
+
+        let all: BitSet = (0..space.predicates.len()).collect();
+        let mut sets: Vec<(BitSet, usize)> = evidence.into_iter().map(|(e, pairs)| (all.difference(&e).collect(), pairs)).collect();
+        sets.sort_by(|(s1, p1), (s2, p2)| p2.cmp(p1).then(s1.iter().cmp(s2.iter())));
+
+        let total = row_num * (row_num - 1);
+        let mut search = Search {
+            sets: &sets,
+            group_of: &space.group_of,
+            budget: (self.max_error * total as f64).floor() as usize,
+            max_predicates: self.max_predicates,
+            found: Vec::new(),
+            levels: vec![0; self.max_predicates]
+        };
+        let remaining: Vec<usize> = (0..sets.len()).collect();
+        let candidates = search.ordering(0..space.predicates.len(), &remaining);
+        search.run(&mut Vec::new(), &remaining, &candidates);
+
+        // a dc and its mirror are the same constraint, the smaller one is kept
+        let found: HashSet<Vec<usize>> = search.found.iter()
+            .map(|dc| {
+                let mut dc = dc.clone();
+                dc.sort();
+                match space.mirror_of(&dc) {
+                    Some(mirror) => dc.min(mirror),
+                    None => dc
+                }
+            })
+            .collect();
+        // the covers are minimal as sets of predicates, but ¬(dept = ∧ grade < ∧ salary =) still follows
+        // from ¬(dept = ∧ grade ≠ ∧ salary =). a dc implying another one is over a subset of its groups
+        let mut by_groups: HashMap<Vec<usize>, Vec<Vec<usize>>> = HashMap::new();
+        for dc in found.iter() {
+            let mirror = space.mirror_of(dc);
+            for form in std::iter::once(dc.clone()).chain(mirror) {
+                by_groups.entry(space.groups(&form)).or_default().push(form);
+            }
+        }
+        let implied = |dc: &Vec<usize>| {
+            let groups = space.groups(dc);
+            (1..1usize << groups.len()).any(|mask| {
+                let subset: Vec<usize> = groups.iter().enumerate().filter(|(i, _)| mask & (1 << i) != 0).map(|(_, g)| *g).collect();
+                by_groups.get(&subset).is_some_and(|forms| forms.iter().any(|other| other != dc && space.implies(other, dc)))
+            })
+        };
+        let mut dcs: Vec<Vec<usize>> = found.iter().filter(|dc| !implied(dc)).cloned().collect();
+        dcs.sort_by_key(|dc| (dc.len(), dc.clone()));
+
+        for dc in dcs {
+            let path: BitSet = dc.iter().cloned().collect();
+            let violations: usize = sets.iter().filter(|(set, _)| set.is_disjoint(&path)).map(|(_, pairs)| pairs).sum();
+            let predicates = dc.iter().map(|p| {
+                let (a, operator, b, same_tuple) = space.predicates[*p];
+                Predicate { left: table.attributes[a].clone(), operator, right: table.attributes[b].clone(), same_tuple }
+            }).collect();
+            res.add(DenialConstraint { predicates, violations, error: violations as f64 / total as f64 });
+        }
+
+        let deepest = search.levels.iter().rposition(|candidates| *candidates > 0).map_or(0, |l| l + 1);
+        for (size, candidates) in search.levels.into_iter().enumerate().take(deepest) {
+            statistics.add_level(candidates, res.iter().filter(|dc| dc.predicates.len() == size + 1).count());
+        }
+        statistics.elapsed = start.elapsed();
+        res.set_statistics(statistics);
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frontend::table::Attribute;
+    use crate::testing::{self, Lcg};
+
+    fn table(names: &[&str], rows: &[Vec<String>]) -> Table {
+        testing::table("employees", names, rows)
+    }
+
+    #[test]
+    fn higher_grades_earn_more() {
+        let rows: Vec<Vec<String>> = [
+            ("sales", 1, 3000), ("sales", 2, 3500), ("sales", 3, 4200), ("sales", 2, 3600),
+            ("it", 1, 4000), ("it", 2, 4100), ("it", 3, 5000), ("hr", 1, 2800), ("hr", 3, 3900)
+        ].iter().map(|(dept, grade, salary)| vec![dept.to_string(), grade.to_string(), salary.to_string()]).collect();
+        let employees = table(&["dept", "grade", "salary"], &rows);
+
+        let dcs = DcMiner::new().execute(&employees);
+        let rendered: Vec<String> = dcs.iter().map(|dc| dc.to_string()).collect();
+        assert!(rendered.contains(&"¬(t.dept = s.dept ∧ t.grade < s.grade ∧ t.salary ≥ s.salary)".to_string()), "{}", dcs);
+
+        // the underpaid senior is a violation of it, two ordered pairs
+        let mut rows = rows;
+        rows.push(vec!["it".to_string(), "3".to_string(), "3900".to_string()]);
+        let employees = table(&["dept", "grade", "salary"], &rows);
+        let exact = DcMiner::new().execute(&employees);
+        assert!(!exact.iter().any(|dc| dc.to_string() == "¬(t.dept = s.dept ∧ t.grade < s.grade ∧ t.salary ≥ s.salary)"), "{}", exact);
+        // 2 of the 90 pairs may violate it, the version with grade ≤ and its 4 violations is still out
+        let approximate = DcMiner::new().max_error(0.03).execute(&employees);
+        let dc = approximate.iter().find(|dc| dc.to_string() == "¬(t.dept = s.dept ∧ t.grade < s.grade ∧ t.salary ≥ s.salary)").unwrap();
+        assert_eq!(dc.violations, 2);
+    }
+
+    fn random_table(seed: u64) -> Table {
+        let mut rng = Lcg::new(seed);
+        let attri_num = 2 + rng.next(2) as usize;
+        let row_num = 6 + rng.next(12) as usize;
+        testing::random_table(&mut rng, attri_num, row_num, |rng| (0..attri_num).map(|_| testing::value(rng.next(5))).collect())
+    }
+
+    // the pairs satisfying every predicate of the dc, row by row
+    fn violations(values: &[Vec<Option<f64>>], dc: &DenialConstraint, null_model: NullModel) -> usize {
+        let value = |row: usize, attri: &Attribute| values[row][attri.rank];
+        let satisfied = |t: usize, s: usize, p: &Predicate| {
+            let (x, y) = (value(t, &p.left), value(if p.same_tuple { t } else { s }, &p.right));
+            match (x, y, p.operator) {
+                (None, None, Operator::Equal | Operator::LessEqual | Operator::GreaterEqual) => null_model == NullModel::Equal,
+                (None, None, Operator::NotEqual) => null_model == NullModel::Distinct,
+                (None, _, op) | (_, None, op) => op == Operator::NotEqual,
+                (Some(x), Some(y), Operator::Equal) => x == y,
+                (Some(x), Some(y), Operator::NotEqual) => x != y,
+                (Some(x), Some(y), Operator::Less) => x < y,
+                (Some(x), Some(y), Operator::Greater) => x > y,
+                (Some(x), Some(y), Operator::LessEqual) => x <= y,
+                (Some(x), Some(y), Operator::GreaterEqual) => x >= y
+            }
+        };
+        let mut cnt = 0;
+        for t in 0..values.len() {
+            for s in (0..values.len()).filter(|s| *s != t) {
+                if dc.predicates.iter().all(|p| satisfied(t, s, p)) {
+                    cnt += 1;
+                }
+            }
+        }
+        cnt
+    }
+
+    #[test]
+    fn dcs_hold_and_are_minimal() {
+        for seed in 0..20 {
+            let table = random_table(seed);
+            let total = table.row_num() * (table.row_num() - 1);
+            let values: Vec<Vec<Option<f64>>> = (0..table.row_num()).map(|row| table.row(row).iter().map(|v| v.parse().ok()).collect()).collect();
+            for null_model in [NullModel::Equal, NullModel::Distinct] {
+                for max_error in [0.0, 0.1] {
+                    let budget = (max_error * total as f64).floor() as usize;
+                    // the predicate space of three columns is a lot larger, so their dcs are kept short
+                    let max_predicates = if table.attributes.len() == 2 { 3 } else { 2 };
+                    let dcs = DcMiner::new().max_error(max_error).max_predicates(max_predicates).null_model(null_model).execute(&table);
+                    for dc in dcs.iter() {
+                        let found = violations(&values, dc, null_model);
+                        assert_eq!(found, dc.violations, "seed {}: {}", seed, dc);
+                        assert!(found <= budget, "seed {}: {}", seed, dc);
+                        for i in 0..dc.predicates.len() {
+                            let mut smaller = dc.clone();
+                            smaller.predicates.remove(i);
+                            assert!(violations(&values, &smaller, null_model) > budget, "seed {}: {} is not minimal", seed, dc);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // the dc with t and s swapped, none when a predicate compares two values of the same row
+    fn mirror(predicates: &[Predicate]) -> Option<Vec<Predicate>> {
+        predicates.iter().map(|p| if p.same_tuple {
+            None
+        } else {
+            Some(Predicate { left: p.right.clone(), operator: p.operator.converse(), right: p.left.clone(), same_tuple: false })
+        }).collect()
+    }
+
+    #[test]
+    fn every_minimal_dc_is_found() {
+        let max_predicates = 3;
+        for seed in 0..40 {
+            let table = random_table(seed);
+            // all the operators apply to two numeric columns and every pair of columns is compared
+            if table.attributes.len() != 2 || (0..2).any(|c| (0..table.row_num()).all(|row| table.is_null(row, c))) {
+                continue;
+            }
+            let total = table.row_num() * (table.row_num() - 1);
+            let values: Vec<Vec<Option<f64>>> = (0..table.row_num()).map(|row| table.row(row).iter().map(|v| v.parse().ok()).collect()).collect();
+
+            // at most one predicate over each pair of operands, t.A op s.A, t.A op s.B, t.B op s.A, t.B op s.B and t.A op t.B
+            let mut candidates: Vec<Vec<Predicate>> = vec![Vec::new()];
+            for (a, b, same_tuple) in [(0, 0, false), (0, 1, false), (1, 0, false), (1, 1, false), (0, 1, true)] {
+                for i in 0..candidates.len() {
+                    if candidates[i].len() < max_predicates {
+                        for operator in Operator::ALL {
+                            let mut candidate = candidates[i].clone();
+                            candidate.push(Predicate { left: table.attributes[a].clone(), operator, right: table.attributes[b].clone(), same_tuple });
+                            candidates.push(candidate);
+                        }
+                    }
+                }
+            }
+
+            let operands = |p: &Predicate| (p.left.rank, p.right.rank, p.same_tuple);
+            // ¬(found) implies ¬(dc) when each predicate of found follows from the one of dc over the same operands
+            let implies = |found: &[Predicate], dc: &[Predicate]| found.iter().all(|q| dc.iter().any(|p| operands(p) == operands(q) && p.operator.implies(q.operator)));
+            let dc = |predicates: Vec<Predicate>| DenialConstraint { predicates, violations: 0, error: 0.0 };
+
+            for null_model in [NullModel::Equal, NullModel::Distinct] {
+                for max_error in [0.0, 0.1] {
+                    let budget = (max_error * total as f64).floor() as usize;
+                    let dcs = DcMiner::new().max_error(max_error).max_predicates(max_predicates).min_overlap(0.0).null_model(null_model).execute(&table);
+
+                    for candidate in candidates.iter().filter(|candidate| !candidate.is_empty()) {
+                        let minimal = (0..candidate.len()).all(|i| {
+                            let mut smaller = candidate.clone();
+                            smaller.remove(i);
+                            violations(&values, &dc(smaller), null_model) > budget
+                        });
+                        if !minimal || violations(&values, &dc(candidate.clone()), null_model) > budget {
+                            continue;
+                        }
+
+                        let forms: Vec<Vec<Predicate>> = std::iter::once(candidate.clone()).chain(mirror(candidate)).collect();
+                        assert!(dcs.iter().any(|found| forms.iter().any(|form| implies(&found.predicates, form))),
+                            "seed {} ({}, {}): {} is missing\n{}", seed, null_model, max_error, dc(candidate.clone()), dcs);
+                    }
+                }
+            }
+        }
+    }
+}
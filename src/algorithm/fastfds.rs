@@ -0,0 +1,189 @@
+use crate::algorithm::Algorithm;
+use crate::frontend::partition::{NullModel, agree_set, column_partitions, compressed_records};
+use crate::frontend::table::Table;
+use crate::dependency::result::Statistics;
+use crate::dependency::fd::FDs;
+
+use bit_set::BitSet;
+use rayon::prelude::*;
+use std::collections::HashSet;
+use std::time::Instant;
+
+// row based discovery for wide tables with few rows: the agree sets of all the row pairs give
+// the difference sets of every attribute A, and the minimal X -> A are the minimal covers of them.
+// the work grows with the square of the rows but not with the lattice of the attributes
+pub struct FastFds {
+    parallel: bool,
+    null_model: NullModel
+}
+
+impl FastFds {
+    pub fn new() -> FastFds {
+        FastFds { parallel: false, null_model: NullModel::Equal }
+    }
+
+    // compare the rows and search the covers of every attribute on all the cores
+    pub fn parallel(mut self) -> FastFds {
+        self.parallel = true;
+        self
+    }
+
+    pub fn null_model(mut self, null_model: NullModel) -> FastFds {
+        self.null_model = null_model;
+        self
+    }
+}
+
+impl Default for FastFds {
+    fn default() -> FastFds {
+        FastFds::new()
+    }
+}
+
+fn agree_sets_of(records: &[Vec<u32>], row: usize) -> HashSet<BitSet> {
+    (row + 1..records.len()).map(|other| agree_set(records, row, other)).collect()
+}
+
+// the attributes outside the agree sets without A, only the minimal ones are kept
+fn difference_sets(agree_sets: &[BitSet], attri_num: usize, a: usize) -> Vec<BitSet> {
+    let mut sets: Vec<BitSet> = agree_sets.iter()
+        .filter(|agree| !agree.contains(a))
+        .map(|agree| (0..attri_num).filter(|b| *b != a && !agree.contains(*b)).collect())
+        .collect();
+    sets.sort_by_key(|set: &BitSet| set.len());
+
+    let mut minimal: Vec<BitSet> = Vec::new();
+    for set in sets {
+        if !minimal.iter().any(|m| m.is_subset(&set)) {
+            minimal.push(set);
+        }
+    }
+    minimal
+}
+
+// the attributes hitting the most of the remaining sets come first
+fn ordering(candidates: impl Iterator<Item = usize>, remaining: &[usize], sets: &[BitSet]) -> Vec<usize> {
+    let mut counted: Vec<(usize, usize)> = candidates
+        .map(|b| (b, remaining.iter().filter(|set| sets[**set].contains(b)).count()))
+        .filter(|(_, count)| *count > 0)
+        .collect();
+    counted.sort_by(|(b1, c1), (b2, c2)| c2.cmp(c1).then(b1.cmp(b2)));
+    counted.into_iter().map(|(b, _)| b).collect()
+}
+
+// a cover is minimal when every attribute of it is the only one hitting some set
+fn is_minimal(cover: &BitSet, sets: &[BitSet]) -> bool {
+    cover.iter().all(|b| sets.iter().any(|set| set.contains(b) && set.intersection(cover).count() == 1))
+}
+
+// depth first search of the covers, an attribute is only followed by the ones after it in the ordering.
+// a path that is not minimal any more is left, since a longer path never makes it minimal again
+fn find_covers(remaining: &[usize], candidates: &[usize], path: &mut BitSet, sets: &[BitSet], covers: &mut Vec<BitSet>) {
+    if !is_minimal(path, sets) {
+        return;
+    }
+    if remaining.is_empty() {
+        covers.push(path.clone());
+        return;
+    }
+
+    for (i, b) in candidates.iter().enumerate() {
+        let next: Vec<usize> = remaining.iter().filter(|set| !sets[**set].contains(*b)).cloned().collect();
+        let next_candidates = ordering(candidates[i + 1..].iter().cloned(), &next, sets);
+
+        // the later attributes can not cover some set any more
+        let reachable: BitSet = next_candidates.iter().cloned().collect();
+        if next.iter().any(|set| sets[*set].is_disjoint(&reachable)) {
+            continue;
+        }
+
+        path.insert(*b);
+        find_covers(&next, &next_candidates, path, sets, covers);
+        path.remove(*b);
+    }
+}
+
+// the minimal lhs of A
+fn minimal_lhs(agree_sets: &[BitSet], attri_num: usize, a: usize) -> Vec<BitSet> {
+    let sets = difference_sets(agree_sets, attri_num, a);
+    // two rows only differ on A
+    if sets.iter().any(|set| set.is_empty()) {
+        return Vec::new();
+    }
+
+    let mut covers = Vec::new();
+    let remaining: Vec<usize> = (0..sets.len()).collect();
+    let candidates = ordering((0..attri_num).filter(|b| *b != a), &remaining, &sets);
+    find_covers(&remaining, &candidates, &mut BitSet::new(), &sets, &mut covers);
+    covers
+}
+
+impl Algorithm for FastFds {
+    type Result = FDs;
+
+    fn execute(&mut self, table: &Table) -> FDs {
+        let start = Instant::now();
+        let mut statistics = Statistics::default();
+        let mut res = FDs::new(table).with_null_model(self.null_model);
+
+        let attri_num = table.attributes.len();
+        let row_num = table.row_num();
+        let records = compressed_records(&column_partitions(table, self.null_model), row_num);
+
+        let agree_sets: HashSet<BitSet> = if self.parallel {
+            (0..row_num).into_par_iter()
+                .map(|row| agree_sets_of(&records, row))
+                .reduce(HashSet::new, |mut a, b| { a.extend(b); a })
+        } else {
+            (0..row_num).flat_map(|row| agree_sets_of(&records, row)).collect()
+        };
+        let agree_sets: Vec<BitSet> = agree_sets.into_iter().collect();
+        log::debug!("{} distinct agree sets of {} rows", agree_sets.len(), row_num);
+
+        let covers: Vec<Vec<BitSet>> = if self.parallel {
+            (0..attri_num).into_par_iter().map(|a| minimal_lhs(&agree_sets, attri_num, a)).collect()
+        } else {
+            (0..attri_num).map(|a| minimal_lhs(&agree_sets, attri_num, a)).collect()
+        };
+
+        let mut fds: Vec<(BitSet, usize)> = covers.into_iter().enumerate()
+            .flat_map(|(a, lhs)| lhs.into_iter().map(move |lhs| (lhs, a)))
+            .collect();
+        fds.sort_by_key(|(lhs, a)| (lhs.len(), lhs.iter().collect::<Vec<_>>(), *a));
+        for (lhs, a) in fds {
+            res.add_from_index(&lhs, a, 0.0);
+        }
+
+        statistics.elapsed = start.elapsed();
+        res.set_statistics(statistics);
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithm::tane::Tane;
+    use crate::testing::{self, fd_set, Lcg};
+
+    // wide tables with a handful of rows, the case the algorithm is made for
+    fn wide_table(seed: u64) -> Table {
+        let mut rng = Lcg::new(seed);
+        let attri_num = 6 + rng.next(5) as usize;
+        let row_num = rng.next(16) as usize;
+        testing::random_table(&mut rng, attri_num, row_num, |rng| (0..attri_num).map(|c| testing::value(rng.next(2 + c as u64 % 4))).collect())
+    }
+
+    #[test]
+    fn same_fds_as_tane() {
+        for seed in 0..40 {
+            let table = wide_table(seed);
+            for null_model in [NullModel::Equal, NullModel::Distinct] {
+                let tane = Tane::new().null_model(null_model).mine(&table);
+                let fastfds = FastFds::new().null_model(null_model).execute(&table);
+                assert_eq!(fd_set(&fastfds), fd_set(&tane), "seed {} ({})", seed, null_model);
+                assert_eq!(fd_set(&FastFds::new().parallel().null_model(null_model).execute(&table)), fd_set(&tane));
+            }
+        }
+    }
+}
@@ -0,0 +1,415 @@
+use crate::algorithm::Algorithm;
+use crate::frontend::partition::{NullModel, ProbeTable, SortedPartition, StrippedPartition};
+use crate::frontend::table::Table;
+use crate::frontend::types::ColumnType;
+use crate::dependency::result::Statistics;
+use crate::dependency::od::ODs;
+
+use bit_set::BitSet;
+use std::collections::HashMap;
+use std::time::Instant;
+
+// set-based discovery of the minimal canonical ods, level by level over the attribute lattice like tane.
+// the constant ods are checked on the stripped partitions, the compatible ones by walking the sorted
+// partition of A and looking for a swap on B inside the classes of the context
+pub struct Fastod {
+    null_model: NullModel,
+    column_types: Option<Vec<ColumnType>>
+}
+
+impl Fastod {
+    pub fn new() -> Fastod {
+        Fastod { null_model: NullModel::Equal, column_types: None }
+    }
+
+    pub fn null_model(mut self, null_model: NullModel) -> Fastod {
+        self.null_model = null_model;
+        self
+    }
+
+    // compare the columns as the given types instead of inferring them from the values
+    pub fn column_types(mut self, column_types: Vec<ColumnType>) -> Fastod {
+        self.column_types = Some(column_types);
+        self
+    }
+}
+
+impl Default for Fastod {
+    fn default() -> Fastod {
+        Fastod::new()
+    }
+}
+
+struct Node {
+    partition: StrippedPartition,
+    // the candidate rhs A of X\A: [] -> A
+    cc: BitSet,
+    // the candidate pairs A < B of X\{A, B}: A ~ B
+    cs: Vec<(usize, usize)>
+}
+
+impl Node {
+    fn new(partition: StrippedPartition) -> Node {
+        Node { partition, cc: BitSet::new(), cs: Vec::new() }
+    }
+}
+
+enum Found {
+    Constant(usize),
+    Compatible(usize, usize)
+}
+
+fn without(x: &BitSet, a: usize) -> BitSet {
+    let mut res = x.clone();
+    res.remove(a);
+    res
+}
+
+// whether two rows of a context class are ordered one way by A and the other way by B.
+// the classes of A are walked in order, keeping the largest rank of B seen so far in every context class
+fn has_swap(context: &StrippedPartition, a: &SortedPartition, b: &SortedPartition, class_of: &mut [u32], max_b: &mut Vec<u32>) -> bool {
+    for (index, class) in context.clusters().iter().enumerate() {
+        for row in class {
+            class_of[*row as usize] = index as u32 + 1;
+        }
+    }
+    max_b.clear();
+    max_b.resize(context.clusters().len() + 1, 0);
+
+    let mut swap = false;
+    let classes = a.classes();
+    let mut start = 0;
+    while start < classes.len() && !swap {
+        // the rows tied on A, in one class or in the adjacent classes of the same rank,
+        // are compared against the earlier classes only
+        let rank = a.rank(classes[start][0] as usize);
+        let end = start + classes[start..].iter().take_while(|class| a.rank(class[0] as usize) == rank).count();
+        let tied = || classes[start..end].iter().flatten().map(|row| *row as usize);
+
+        swap = tied().any(|row| {
+            let (c, rank) = (class_of[row] as usize, b.rank(row));
+            c != 0 && rank != 0 && rank < max_b[c]
+        });
+        for row in tied() {
+            let c = class_of[row] as usize;
+            if c != 0 {
+                max_b[c] = max_b[c].max(b.rank(row));
+            }
+        }
+        start = end;
+    }
+
+    for class in context.clusters() {
+        for row in class {
+            class_of[*row as usize] = 0;
+        }
+    }
+    swap
+}
+
+// the candidates of X from the level below, cc(X) is the intersection of cc(X\C) and the pairs of cs(X)
+// are the ones every cs(X\D) keeps for D in X\{A, B}
+fn initial_candidates(x: &BitSet, level: usize, previous: &HashMap<BitSet, Node>) -> (BitSet, Vec<(usize, usize)>) {
+    let mut cc: Option<BitSet> = None;
+    for c in x.iter() {
+        let sub = &previous[&without(x, c)].cc;
+        match cc.as_mut() {
+            Some(cc) => cc.intersect_with(sub),
+            None => cc = Some(sub.clone())
+        }
+    }
+
+    let cs = if level == 2 {
+        let pair: Vec<usize> = x.iter().collect();
+        vec![(pair[0], pair[1])]
+    } else {
+        let first = x.iter().next().unwrap();
+        let mut pairs: Vec<(usize, usize)> = x.iter().filter(|c| *c != first)
+            .flat_map(|c| previous[&without(x, c)].cs.iter().cloned())
+            .chain(previous[&without(x, first)].cs.iter().cloned())
+            .collect();
+        pairs.sort();
+        pairs.dedup();
+        pairs.retain(|(a, b)| x.iter()
+            .filter(|d| d != a && d != b)
+            .all(|d| previous[&without(x, d)].cs.contains(&(*a, *b))));
+        pairs
+    };
+
+    (cc.unwrap_or_default(), cs)
+}
+
+fn next_level(level: &HashMap<BitSet, Node>, probe: &mut ProbeTable) -> HashMap<BitSet, Node> {
+    let mut prefix_blocks: HashMap<BitSet, Vec<&BitSet>> = HashMap::new();
+    for x in level.keys() {
+        let prefix = without(x, x.iter().max().unwrap());
+        prefix_blocks.entry(prefix).or_default().push(x);
+    }
+
+    let mut next = HashMap::new();
+    for block in prefix_blocks.values() {
+        for i in 0..block.len() {
+            for j in (i + 1)..block.len() {
+                let x: BitSet = block[i].union(block[j]).collect();
+                if x.iter().all(|c| level.contains_key(&without(&x, c))) {
+                    let partition = probe.product(&level[block[i]].partition, &level[block[j]].partition);
+                    next.insert(x, Node::new(partition));
+                }
+            }
+        }
+    }
+    next
+}
+
+impl Algorithm for Fastod {
+    type Result = ODs;
+
+    fn execute(&mut self, table: &Table) -> ODs {
+        let start = Instant::now();
+        let mut statistics = Statistics::default();
+
+        let attri_num = table.attributes.len();
+        let row_num = table.row_num();
+        let column_types = match self.column_types.clone() {
+            Some(types) => types,
+            None => (0..attri_num).map(|col| ColumnType::infer(table, col)).collect()
+        };
+        log::debug!("Column types: {:?}", column_types);
+        let mut res = ODs::new(table, column_types.clone()).with_null_model(self.null_model);
+
+        let sorted: Vec<SortedPartition> = (0..attri_num)
+            .map(|col| SortedPartition::new(table, col, column_types[col], self.null_model))
+            .collect();
+
+        let all: BitSet = (0..attri_num).collect();
+        let mut root = Node::new(StrippedPartition::new(row_num));
+        root.cc = all.clone();
+
+        // the levels l - 2, l - 1 and l, the contexts of the pairs are two levels down
+        let mut context_level: HashMap<BitSet, Node> = HashMap::new();
+        let mut previous: HashMap<BitSet, Node> = [(BitSet::new(), root)].into_iter().collect();
+        let mut current: HashMap<BitSet, Node> = sorted.iter().enumerate()
+            .map(|(col, partition)| ([col].into_iter().collect(), Node::new(partition.stripped())))
+            .collect();
+
+        let mut probe = ProbeTable::new(row_num);
+        let mut class_of = vec![0u32; row_num];
+        let mut max_b = Vec::new();
+
+        let mut l = 1;
+        while !current.is_empty() && l <= attri_num {
+            let mut found: Vec<(BitSet, Found)> = Vec::new();
+
+            for (x, node) in current.iter_mut() {
+                (node.cc, node.cs) = initial_candidates(x, l, &previous);
+
+                for a in x.intersection(&node.cc.clone()) {
+                    let context = without(x, a);
+                    if previous[&context].partition.get_error() == node.partition.get_error() {
+                        found.push((context, Found::Constant(a)));
+                        node.cc.remove(a);
+                        node.cc.difference_with(&all.difference(x).collect());
+                    }
+                }
+
+                let cs = std::mem::take(&mut node.cs);
+                for (a, b) in cs {
+                    // the context already makes A or B constant
+                    if !previous[&without(x, b)].cc.contains(a) || !previous[&without(x, a)].cc.contains(b) {
+                        continue;
+                    }
+                    let context = without(&without(x, a), b);
+                    if has_swap(&context_level[&context].partition, &sorted[a], &sorted[b], &mut class_of, &mut max_b) {
+                        node.cs.push((a, b));
+                    } else {
+                        found.push((context, Found::Compatible(a, b)));
+                    }
+                }
+            }
+
+            statistics.add_level(current.len(), found.len());
+            let key = |(context, od): &(BitSet, Found)| match od {
+                Found::Constant(a) => (context.iter().collect::<Vec<_>>(), *a, None),
+                Found::Compatible(a, b) => (context.iter().collect::<Vec<_>>(), *a, Some(*b))
+            };
+            found.sort_by_key(key);
+            for (context, od) in found {
+                match od {
+                    Found::Constant(a) => res.add_constant(&context, a),
+                    Found::Compatible(a, b) => res.add_compatible(&context, a, b)
+                }
+            }
+
+            // no od has a candidate on X or above it any more
+            current.retain(|_, node| !node.cc.is_empty() || !node.cs.is_empty());
+            let next = next_level(&current, &mut probe);
+            context_level = std::mem::replace(&mut previous, std::mem::replace(&mut current, next));
+            l += 1;
+        }
+
+        statistics.elapsed = start.elapsed();
+        res.set_statistics(statistics);
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithm::tane::Tane;
+    use crate::dependency::od::OrderDependency;
+    use crate::testing::{self, fd_set, Lcg};
+    use std::collections::HashSet;
+
+    fn table(names: &[&str], rows: &[&[&str]]) -> Table {
+        let rows: Vec<Vec<String>> = rows.iter().map(|row| row.iter().map(|v| v.to_string()).collect()).collect();
+        testing::table("invoices", names, &rows)
+    }
+
+    fn rendered(ods: &ODs) -> Vec<String> {
+        ods.iter().map(|od| od.to_string()).collect()
+    }
+
+    #[test]
+    fn invoice_numbers_follow_the_dates() {
+        let table = table(&["date", "invoice_no", "amount"], &[
+            &["2023-01-05", "9", "100.5"],
+            &["2023-01-05", "10", "20"],
+            &["2023-02-11", "11", "100.5"],
+            &["2022-12-30", "8", "7"],
+            &["2023-03-01", "12", "20"]
+        ]);
+        let ods = Fastod::new().execute(&table);
+        assert_eq!(ods.column_types(), &[ColumnType::Date, ColumnType::Numeric, ColumnType::Numeric]);
+
+        let found = rendered(&ods);
+        assert!(found.contains(&"{}: date ~ invoice_no".to_string()), "{}", ods);
+        assert!(found.contains(&"{invoice_no}: [] -> date".to_string()), "{}", ods);
+        assert!(!found.iter().any(|od| od.contains("date ~ amount")), "{}", ods);
+
+        // compared as strings 10 sorts before 9
+        let ods = Fastod::new().column_types(vec![ColumnType::Date, ColumnType::String, ColumnType::Numeric]).execute(&table);
+        assert!(!rendered(&ods).contains(&"{}: date ~ invoice_no".to_string()), "{}", ods);
+    }
+
+    #[test]
+    fn equal_numbers_are_ties_but_no_equal_values() {
+        let table = table(&["price", "rank", "tag"], &[
+            &["7.0", "2", "x"],
+            &["7", "1", "y"],
+            &["8", "3", "x"],
+            &["7.00", "1", "y"]
+        ]);
+        let ods = Fastod::new().execute(&table);
+
+        // 7 and 7.0 are the values tane sees
+        let mut constants: Vec<(Vec<usize>, usize)> = ods.iter().filter_map(|od| match od {
+            OrderDependency::Constant { dependant, .. } => Some((od.context().0.iter().map(|a| a.rank).collect(), dependant.rank)),
+            _ => None
+        }).collect();
+        constants.sort();
+        assert_eq!(constants, fd_set(&Tane::new().mine(&table)));
+        assert!(rendered(&ods).contains(&"{price}: [] -> rank".to_string()), "{}", ods);
+        // but they are tied, the rank going down between them is no swap
+        assert!(rendered(&ods).contains(&"{}: price ~ rank".to_string()), "{}", ods);
+    }
+
+    fn random_table(seed: u64) -> Table {
+        let mut rng = Lcg::new(seed);
+        let attri_num = 2 + rng.next(4) as usize;
+        let row_num = rng.next(25) as usize;
+        let domains: Vec<u64> = (0..attri_num).map(|_| 1 + rng.next(6)).collect();
+        testing::random_table(&mut rng, attri_num, row_num, |rng| {
+            let base = rng.next(8);
+            domains.iter().map(|d| match rng.next(*d + 1) {
+                0 if *d > 3 => String::new(),
+                v => (base * v / 2).to_string()
+            }).collect()
+        })
+    }
+
+    fn same(table: &Table, r: usize, s: usize, c: usize, null_model: NullModel) -> bool {
+        table.code(r, c) == table.code(s, c) && (null_model == NullModel::Equal || !table.is_null(r, c))
+    }
+
+    // any two rows agreeing on the context agree on a
+    fn constant(table: &Table, context: &[usize], a: usize, null_model: NullModel) -> bool {
+        (0..table.row_num()).all(|r| (r + 1..table.row_num()).all(|s| {
+            !context.iter().all(|c| same(table, r, s, *c, null_model)) || same(table, r, s, a, null_model)
+        }))
+    }
+
+    // a swap in some class of the context, row by row
+    fn holds(table: &Table, context: &[usize], a: usize, b: usize, null_model: NullModel) -> bool {
+        let (ta, tb) = (ColumnType::infer(table, a), ColumnType::infer(table, b));
+        let (sa, sb) = (SortedPartition::new(table, a, ta, null_model), SortedPartition::new(table, b, tb, null_model));
+        for r in 0..table.row_num() {
+            for s in 0..table.row_num() {
+                if r == s || !context.iter().all(|c| same(table, r, s, *c, null_model)) {
+                    continue;
+                }
+                let (ar, as_, br, bs) = (sa.rank(r), sa.rank(s), sb.rank(r), sb.rank(s));
+                if ar != 0 && as_ != 0 && br != 0 && bs != 0 && ar < as_ && br > bs {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    #[test]
+    fn constants_are_the_fds_and_pairs_hold() {
+        for seed in 0..60 {
+            let table = random_table(seed);
+            for null_model in [NullModel::Equal, NullModel::Distinct] {
+                let ods = Fastod::new().null_model(null_model).execute(&table);
+                let mut constants: Vec<(Vec<usize>, usize)> = Vec::new();
+                for od in ods.iter() {
+                    let context: Vec<usize> = od.context().0.iter().map(|a| a.rank).collect();
+                    match od {
+                        OrderDependency::Constant { dependant, .. } => constants.push((context, dependant.rank)),
+                        OrderDependency::Compatible { left, right, .. } => assert!(holds(&table, &context, left.rank, right.rank, null_model), "seed {}: {}", seed, od)
+                    }
+                }
+                constants.sort();
+
+                let tane = Tane::new().null_model(null_model).mine(&table);
+                assert_eq!(constants, fd_set(&tane), "seed {} ({})", seed, null_model);
+            }
+        }
+    }
+
+    #[test]
+    fn every_minimal_pair_is_found() {
+        for seed in 0..60 {
+            let table = random_table(seed);
+            let attri_num = table.attributes.len();
+            for null_model in [NullModel::Equal, NullModel::Distinct] {
+                let ods = Fastod::new().null_model(null_model).execute(&table);
+                let found: HashSet<(Vec<usize>, usize, usize)> = ods.iter().filter_map(|od| match od {
+                    OrderDependency::Compatible { left, right, .. } => Some((od.context().0.iter().map(|a| a.rank).collect(), left.rank, right.rank)),
+                    _ => None
+                }).collect();
+
+                for mask in 0..1usize << attri_num {
+                    let context: Vec<usize> = (0..attri_num).filter(|c| mask & 1 << c != 0).collect();
+                    for a in (0..attri_num).filter(|a| !context.contains(a)) {
+                        for b in (a + 1..attri_num).filter(|b| !context.contains(b)) {
+                            // a constant side makes the pair trivial, a smaller context makes it no minimal
+                            if constant(&table, &context, a, null_model) || constant(&table, &context, b, null_model) || !holds(&table, &context, a, b, null_model) {
+                                continue;
+                            }
+                            let smaller = context.iter().any(|c| {
+                                let sub: Vec<usize> = context.iter().filter(|d| *d != c).cloned().collect();
+                                holds(&table, &sub, a, b, null_model)
+                            });
+                            if !smaller {
+                                assert!(found.contains(&(context.clone(), a, b)), "seed {} ({}): {:?}: {} ~ {} is missing\n{}", seed, null_model, context, a, b, ods);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use bit_set::BitSet;
+
+// prefix tree of the lhs of fds X -> A, the attributes along a path are ascending.
+// a node keeps the rhs of its own lhs and the union of the rhs below it, which only prunes lookups
+// and may still contain rhs removed since
+pub struct FdTree {
+    attri_num: usize,
+    root: FdTreeNode
+}
+
+struct FdTreeNode {
+    children: Vec<Option<Box<FdTreeNode>>>,
+    rhs: BitSet,
+    subtree: BitSet
+}
+
+impl FdTreeNode {
+    fn new(attri_num: usize) -> FdTreeNode {
+        FdTreeNode { children: (0..attri_num).map(|_| None).collect(), rhs: BitSet::new(), subtree: BitSet::new() }
+    }
+
+    fn contains_generalization(&self, lhs: &[usize], a: usize) -> bool {
+        if self.rhs.contains(a) {
+            return true;
+        }
+        if !self.subtree.contains(a) {
+            return false;
+        }
+
+        lhs.iter().enumerate().any(|(i, b)| {
+            self.children[*b].as_ref().is_some_and(|child| child.contains_generalization(&lhs[i + 1..], a))
+        })
+    }
+
+    fn remove_generalizations(&mut self, lhs: &[usize], a: usize, path: &mut BitSet, removed: &mut Vec<BitSet>) {
+        if self.rhs.remove(a) {
+            removed.push(path.clone());
+        }
+        if !self.subtree.contains(a) {
+            return;
+        }
+
+        for (i, b) in lhs.iter().enumerate() {
+            if let Some(child) = self.children[*b].as_mut() {
+                path.insert(*b);
+                child.remove_generalizations(&lhs[i + 1..], a, path, removed);
+                path.remove(*b);
+            }
+        }
+    }
+
+    fn collect(&self, depth: Option<usize>, path: &mut BitSet, fds: &mut Vec<(BitSet, BitSet)>) {
+        if depth.is_none_or(|depth| path.len() == depth) && !self.rhs.is_empty() {
+            fds.push((path.clone(), self.rhs.clone()));
+        }
+        if depth.is_some_and(|depth| path.len() >= depth) || self.subtree.is_empty() {
+            return;
+        }
+
+        for (b, child) in self.children.iter().enumerate() {
+            if let Some(child) = child {
+                path.insert(b);
+                child.collect(depth, path, fds);
+                path.remove(b);
+            }
+        }
+    }
+}
+
+impl FdTree {
+    pub fn new(attri_num: usize) -> FdTree {
+        FdTree { attri_num, root: FdTreeNode::new(attri_num) }
+    }
+
+    // {} -> A for every attribute, the start of the top down searches
+    pub fn most_general(attri_num: usize) -> FdTree {
+        let mut tree = FdTree::new(attri_num);
+        for a in 0..attri_num {
+            tree.add(&BitSet::new(), a);
+        }
+        tree
+    }
+
+    pub fn attri_num(&self) -> usize {
+        self.attri_num
+    }
+
+    pub fn add(&mut self, lhs: &BitSet, a: usize) {
+        let attri_num = self.attri_num;
+        let mut node = &mut self.root;
+        node.subtree.insert(a);
+        for b in lhs.iter() {
+            node = node.children[b].get_or_insert_with(|| Box::new(FdTreeNode::new(attri_num)));
+            node.subtree.insert(a);
+        }
+        node.rhs.insert(a);
+    }
+
+    pub fn remove(&mut self, lhs: &BitSet, a: usize) {
+        let mut node = &mut self.root;
+        for b in lhs.iter() {
+            match node.children[b].as_mut() {
+                Some(child) => node = child,
+                None => return
+            }
+        }
+        node.rhs.remove(a);
+    }
+
+    // whether Y -> A is in the tree for some Y ⊆ lhs
+    pub fn contains_generalization(&self, lhs: &BitSet, a: usize) -> bool {
+        let lhs: Vec<usize> = lhs.iter().collect();
+        self.root.contains_generalization(&lhs, a)
+    }
+
+    // removes every Y -> A with Y ⊆ lhs and returns the Y
+    pub fn remove_generalizations(&mut self, lhs: &BitSet, a: usize) -> Vec<BitSet> {
+        let lhs: Vec<usize> = lhs.iter().collect();
+        let mut removed = Vec::new();
+        self.root.remove_generalizations(&lhs, a, &mut BitSet::new(), &mut removed);
+        removed
+    }
+
+    // the lhs of the given size with their rhs
+    pub fn level(&self, size: usize) -> Vec<(BitSet, BitSet)> {
+        let mut fds = Vec::new();
+        self.root.collect(Some(size), &mut BitSet::new(), &mut fds);
+        fds
+    }
+
+    pub fn fds(&self) -> Vec<(BitSet, BitSet)> {
+        let mut fds = Vec::new();
+        self.root.collect(None, &mut BitSet::new(), &mut fds);
+        fds
+    }
+}
@@ -0,0 +1,281 @@
+use crate::algorithm::Algorithm;
+use crate::algorithm::fd_tree::FdTree;
+use crate::frontend::partition::{NullModel, UNIQUE, agree_set, column_partitions, compressed_records};
+use crate::frontend::table::Table;
+use crate::dependency::result::Statistics;
+use crate::dependency::fd::FDs;
+
+use bit_set::BitSet;
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+use std::time::Instant;
+
+// hybrid discovery: the non-fds of sampled row pairs give the candidates of a top down fd tree,
+// which is validated level by level. each phase hands over to the other once it stops paying off,
+// only the compressed records and one partition per column are kept in memory
+pub struct Hyfd {
+    null_model: NullModel,
+    efficiency: f64
+}
+
+impl Hyfd {
+    pub fn new() -> Hyfd {
+        Hyfd { null_model: NullModel::Equal, efficiency: 0.01 }
+    }
+
+    pub fn null_model(mut self, null_model: NullModel) -> Hyfd {
+        self.null_model = null_model;
+        self
+    }
+
+    // the share of new non-fds per comparison below which the sampling stops,
+    // and the share of invalid candidates per level above which the validation goes back to sampling
+    pub fn efficiency(mut self, efficiency: f64) -> Hyfd {
+        self.efficiency = efficiency;
+        self
+    }
+}
+
+impl Default for Hyfd {
+    fn default() -> Hyfd {
+        Hyfd::new()
+    }
+}
+
+// compares the rows of every cluster with the rows a window further,
+// the columns with the most new non-fds per comparison get the next larger window first
+struct Sampler<'a> {
+    clusters: &'a [Vec<Vec<u32>>],
+    windows: Vec<usize>,
+    efficiencies: Vec<f64>
+}
+
+impl<'a> Sampler<'a> {
+    // the rows of each cluster are expected in the order of their records, see sort_clusters
+    fn new(clusters: &'a [Vec<Vec<u32>>]) -> Sampler<'a> {
+        let attri_num = clusters.len();
+        Sampler { clusters, windows: vec![0; attri_num], efficiencies: vec![f64::MAX; attri_num] }
+    }
+
+    fn run_window(&mut self, column: usize, records: &[Vec<u32>], non_fds: &mut HashSet<BitSet>, found: &mut Vec<BitSet>) {
+        self.windows[column] += 1;
+        let window = self.windows[column];
+
+        let mut comparisons = 0;
+        let before = found.len();
+        for cluster in self.clusters[column].iter().filter(|c| c.len() > window) {
+            for i in 0..cluster.len() - window {
+                comparisons += 1;
+                let agree = agree_set(records, cluster[i] as usize, cluster[i + window] as usize);
+                if non_fds.insert(agree.clone()) {
+                    found.push(agree);
+                }
+            }
+        }
+
+        self.efficiencies[column] = if comparisons == 0 { 0.0 } else { (found.len() - before) as f64 / comparisons as f64 };
+    }
+
+    fn run(&mut self, threshold: f64, records: &[Vec<u32>], non_fds: &mut HashSet<BitSet>) -> Vec<BitSet> {
+        let mut found = Vec::new();
+        loop {
+            let best = (0..self.clusters.len()).max_by(|a, b| self.efficiencies[*a].total_cmp(&self.efficiencies[*b]));
+            match best {
+                Some(column) if self.efficiencies[column] >= threshold => self.run_window(column, records, non_fds, &mut found),
+                _ => return found
+            }
+        }
+    }
+}
+
+// similar rows end up next to each other, the validation does not care about the order
+fn sort_clusters(clusters: &mut [Vec<Vec<u32>>], records: &[Vec<u32>]) {
+    for column in clusters.iter_mut() {
+        for cluster in column.iter_mut() {
+            cluster.sort_by(|r, s| records[*r as usize].cmp(&records[*s as usize]));
+        }
+    }
+}
+
+// every removed candidate X -> A below a non-fd is specialized by an attribute outside the non-fd
+fn induce(tree: &mut FdTree, non_fds: Vec<BitSet>) {
+    let attri_num = tree.attri_num();
+    let mut non_fds = non_fds;
+    non_fds.sort_by_key(|agree| Reverse(agree.len()));
+
+    for agree in non_fds {
+        for a in (0..attri_num).filter(|a| !agree.contains(*a)) {
+            for lhs in tree.remove_generalizations(&agree, a) {
+                for b in (0..attri_num).filter(|b| *b != a && !agree.contains(*b)) {
+                    let mut specialized = lhs.clone();
+                    specialized.insert(b);
+                    if !tree.contains_generalization(&specialized, a) {
+                        tree.add(&specialized, a);
+                    }
+                }
+            }
+        }
+    }
+}
+
+// the rhs of X that hold, and a pair of rows for every rhs that does not
+fn validate(lhs: &BitSet, rhs: &BitSet, records: &[Vec<u32>], clusters: &[Vec<Vec<u32>>]) -> (BitSet, Vec<(usize, usize)>) {
+    let mut valid = rhs.clone();
+    let mut violations = Vec::new();
+    let mut check = |valid: &mut BitSet, first: usize, row: usize| {
+        let broken: Vec<usize> = valid.iter().filter(|a| records[first][*a] == UNIQUE || records[first][*a] != records[row][*a]).collect();
+        if !broken.is_empty() {
+            broken.iter().for_each(|a| { valid.remove(*a); });
+            violations.push((first, row));
+        }
+    };
+
+    let mut attributes = lhs.iter();
+    match attributes.next() {
+        None => {
+            for row in 1..records.len() {
+                check(&mut valid, 0, row);
+                if valid.is_empty() {
+                    break;
+                }
+            }
+        }
+        Some(pivot) => {
+            let rest: Vec<usize> = attributes.collect();
+            'clusters: for cluster in clusters[pivot].iter() {
+                let mut firsts = HashMap::<Vec<u32>, usize>::new();
+                for row in cluster.iter().map(|row| *row as usize) {
+                    let key: Vec<u32> = rest.iter().map(|c| records[row][*c]).collect();
+                    if key.contains(&UNIQUE) {
+                        continue;
+                    }
+                    match firsts.get(&key) {
+                        Some(first) => check(&mut valid, *first, row),
+                        None => { firsts.insert(key, row); }
+                    }
+                    if valid.is_empty() {
+                        break 'clusters;
+                    }
+                }
+            }
+        }
+    }
+
+    (valid, violations)
+}
+
+impl Algorithm for Hyfd {
+    type Result = FDs;
+
+    fn execute(&mut self, table: &Table) -> FDs {
+        let start = Instant::now();
+        let mut statistics = Statistics::default();
+        let mut res = FDs::new(table).with_null_model(self.null_model);
+
+        let attri_num = table.attributes.len();
+        let row_num = table.row_num();
+        let partitions = column_partitions(table, self.null_model);
+        let records = compressed_records(&partitions, row_num);
+        let mut clusters: Vec<Vec<Vec<u32>>> = partitions.into_iter().map(|p| p.clusters().clone()).collect();
+        sort_clusters(&mut clusters, &records);
+
+        let mut sampler = Sampler::new(&clusters);
+        let mut tree = FdTree::most_general(attri_num);
+        let mut non_fds = HashSet::<BitSet>::new();
+        let mut threshold = self.efficiency;
+        let mut suggestions: Vec<(usize, usize)> = Vec::new();
+        // the candidates and the valid fds of every lhs size
+        let mut levels = vec![(0, 0); attri_num];
+        let mut level = 0;
+
+        loop {
+            let mut found: Vec<BitSet> = suggestions.drain(..)
+                .map(|(r, s)| agree_set(&records, r, s))
+                .filter(|agree| non_fds.insert(agree.clone()))
+                .collect();
+            found.extend(sampler.run(threshold, &records, &mut non_fds));
+            log::debug!("Sampling with threshold {} found {} non-fds", threshold, found.len());
+            induce(&mut tree, found);
+            threshold /= 2.0;
+
+            // the validation resumes at the first level it has not finished
+            let mut switch = false;
+            while level < attri_num && !switch {
+                let candidates = tree.level(level);
+                let mut invalid = 0;
+                for (lhs, rhs) in candidates.iter() {
+                    let (valid, violations) = validate(lhs, rhs, &records, &clusters);
+                    levels[level].0 += rhs.len();
+                    levels[level].1 += valid.len();
+                    suggestions.extend(violations);
+
+                    for a in rhs.difference(&valid) {
+                        invalid += 1;
+                        tree.remove(lhs, a);
+                        for b in (0..attri_num).filter(|b| *b != a && !lhs.contains(*b)) {
+                            let mut specialized = lhs.clone();
+                            specialized.insert(b);
+                            if !tree.contains_generalization(&specialized, a) {
+                                tree.add(&specialized, a);
+                            }
+                        }
+                    }
+                }
+
+                let checked: usize = candidates.iter().map(|(_, rhs)| rhs.len()).sum();
+                log::debug!("Validated {} candidates of level {}, {} invalid", checked, level, invalid);
+                switch = invalid as f64 > self.efficiency * checked as f64;
+                level += 1;
+            }
+
+            if !switch {
+                break;
+            }
+        }
+
+        let mut fds = tree.fds();
+        fds.sort_by_key(|(lhs, _)| (lhs.len(), lhs.iter().collect::<Vec<_>>()));
+        for (lhs, rhs) in fds {
+            for a in rhs.iter() {
+                res.add_from_index(&lhs, a, 0.0);
+            }
+        }
+
+        let deepest = levels.iter().rposition(|(candidates, _)| *candidates > 0).map_or(0, |l| l + 1);
+        for (candidates, found) in levels.into_iter().take(deepest) {
+            statistics.add_level(candidates, found);
+        }
+        statistics.elapsed = start.elapsed();
+        res.set_statistics(statistics);
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithm::tane::Tane;
+    use crate::testing::{self, fd_set, Lcg};
+
+    // small domains give many fds of every size
+    fn random_table(seed: u64, attri_num: usize, row_num: usize) -> Table {
+        let mut rng = Lcg::new(seed);
+        let domains: Vec<u64> = (0..attri_num).map(|_| 1 + rng.next(row_num as u64 / 2 + 2)).collect();
+        testing::random_table(&mut rng, attri_num, row_num, |rng| domains.iter().map(|d| testing::value(rng.next(*d + 1))).collect())
+    }
+
+    #[test]
+    fn same_fds_as_tane() {
+        for seed in 0..60 {
+            let table = random_table(seed, 3 + seed as usize % 5, seed as usize % 40);
+            for null_model in [NullModel::Equal, NullModel::Distinct] {
+                let tane = Tane::new().null_model(null_model).mine(&table);
+                // a high efficiency switches between the phases all the time
+                for efficiency in [0.01, 0.5] {
+                    let hyfd = Hyfd::new().null_model(null_model).efficiency(efficiency).execute(&table);
+                    assert_eq!(fd_set(&hyfd), fd_set(&tane), "seed {} ({})", seed, null_model);
+                }
+            }
+        }
+    }
+}
@@ -2,8 +2,26 @@ use crate::dependency::result::AlgorithmResult;
 use crate::frontend::table::Table;
 
 pub trait Algorithm {
-    fn execute(&mut self, table: &Table) -> Box<dyn AlgorithmResult>;
+    type Result: AlgorithmResult;
+
+    fn execute(&mut self, table: &Table) -> Self::Result;
+}
+
+// algorithms that mine dependencies across several tables
+pub trait MultiTableAlgorithm {
+    type Result: AlgorithmResult;
+
+    fn execute_tables(&mut self, tables: &[Table]) -> Self::Result;
 }
 
 pub mod tane;
-pub mod cluster;
\ No newline at end of file
+pub mod cluster;
+pub mod ucc;
+pub mod spider;
+pub mod validate;
+pub mod fd_tree;
+pub mod hyfd;
+pub mod fastfds;
+pub mod cfd;
+pub mod fastod;
+pub mod dc;
\ No newline at end of file
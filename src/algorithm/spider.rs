@@ -0,0 +1,266 @@
+use crate::algorithm::{Algorithm, MultiTableAlgorithm};
+use crate::frontend::table::Table;
+use crate::dependency::result::Statistics;
+use crate::dependency::ind::INDs;
+
+use std::cmp::Reverse;
+use std::collections::{BTreeSet, BinaryHeap, HashMap, HashSet};
+use std::time::Instant;
+
+// a column is addressed by (table index, attribute index)
+type Column = (usize, usize);
+
+// an n-ary candidate, the dependent and referenced columns are paired by position
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct Candidate {
+    dependent_table: usize,
+    referenced_table: usize,
+    pairs: Vec<(usize, usize)>
+}
+
+// unary inds by a sorted merge of the distinct values of all columns,
+// followed by level-wise generation of the n-ary candidates
+pub struct Spider {
+    max_arity: Option<usize>
+}
+
+impl Spider {
+    pub fn new() -> Spider {
+        Spider { max_arity: None }
+    }
+
+    pub fn with_max_arity(max_arity: usize) -> Spider {
+        Spider { max_arity: Some(max_arity) }
+    }
+}
+
+impl Default for Spider {
+    fn default() -> Spider {
+        Spider::new()
+    }
+}
+
+impl Algorithm for Spider {
+    type Result = INDs;
+
+    fn execute(&mut self, table: &Table) -> INDs {
+        self.execute_tables(std::slice::from_ref(table))
+    }
+}
+
+impl MultiTableAlgorithm for Spider {
+    type Result = INDs;
+
+    fn execute_tables(&mut self, tables: &[Table]) -> INDs {
+        let start = Instant::now();
+        let mut statistics = Statistics::default();
+        let mut res = INDs::new(tables);
+
+        // every ordered pair of distinct columns is a unary candidate
+        let columns: usize = tables.iter().map(|table| table.attributes.len()).sum();
+        let mut level = unary(tables);
+        statistics.add_level(columns * columns.saturating_sub(1), level.len());
+        level.sort_by(|a, b| (a.dependent_table, &a.pairs, a.referenced_table).cmp(&(b.dependent_table, &b.pairs, b.referenced_table)));
+
+        let mut arity = 1;
+        while !level.is_empty() {
+            for ind in level.iter() {
+                let (dependent, referenced): (Vec<usize>, Vec<usize>) = ind.pairs.iter().cloned().unzip();
+                res.add_from_index(ind.dependent_table, &dependent, ind.referenced_table, &referenced);
+            }
+
+            arity += 1;
+            if let Some(max_arity) = self.max_arity {
+                if arity > max_arity {
+                    break;
+                }
+            }
+
+            let candidates = generate_next_level(&level);
+            let generated = candidates.len();
+            level = candidates.into_iter()
+                .filter(|candidate| validate(tables, candidate))
+                .collect();
+            if generated > 0 {
+                statistics.add_level(generated, level.len());
+            }
+        }
+
+        statistics.elapsed = start.elapsed();
+        res.set_statistics(statistics);
+        res
+    }
+}
+
+fn unary(tables: &[Table]) -> Vec<Candidate> {
+    // nulls are missing data, they neither depend on nor reference anything
+    let mut columns: Vec<(Column, Vec<&str>)> = Vec::new();
+    for (t, table) in tables.iter().enumerate() {
+        for c in 0..table.attributes.len() {
+            let column = &table.columns[c];
+            let codes: HashSet<u32> = column.codes.iter().cloned().collect();
+            let values: BTreeSet<&str> = codes.into_iter()
+                .filter(|code| !column.dictionary.is_null(*code))
+                .map(|code| column.dictionary.decode(code))
+                .collect();
+            columns.push(((t, c), values.into_iter().collect()));
+        }
+    }
+
+    let all: HashSet<usize> = (0..columns.len()).collect();
+    let mut refs: Vec<HashSet<usize>> = (0..columns.len()).map(|dep| {
+        let mut r = all.clone();
+        r.remove(&dep);
+        r
+    }).collect();
+
+    let mut cursor = vec![0usize; columns.len()];
+    let mut heap = BinaryHeap::new();
+    for (i, (_, values)) in columns.iter().enumerate() {
+        if let Some(value) = values.first() {
+            heap.push(Reverse((*value, i)));
+        }
+    }
+
+    while let Some(Reverse((value, first))) = heap.pop() {
+        let mut group = HashSet::new();
+        group.insert(first);
+        while let Some(Reverse((next, _))) = heap.peek() {
+            if *next != value {
+                break;
+            }
+            let Reverse((_, i)) = heap.pop().unwrap();
+            group.insert(i);
+        }
+
+        for i in group.iter() {
+            refs[*i].retain(|r| group.contains(r));
+
+            cursor[*i] += 1;
+            if let Some(value) = columns[*i].1.get(cursor[*i]) {
+                heap.push(Reverse((*value, *i)));
+            }
+        }
+    }
+
+    let mut res = Vec::new();
+    for (dep, referenced) in refs.into_iter().enumerate() {
+        if columns[dep].1.is_empty() {
+            continue;
+        }
+        let (dt, dc) = columns[dep].0;
+        for r in referenced {
+            let (rt, rc) = columns[r].0;
+            res.push(Candidate { dependent_table: dt, referenced_table: rt, pairs: vec![(dc, rc)] });
+        }
+    }
+    res
+}
+
+// the candidates sharing the tables and all but the last pair
+type PrefixBlocks<'a> = HashMap<(usize, usize, &'a [(usize, usize)]), Vec<&'a Candidate>>;
+
+fn generate_next_level(level: &[Candidate]) -> Vec<Candidate> {
+    let valid: HashSet<&Candidate> = level.iter().collect();
+
+    let mut prefix_blocks = PrefixBlocks::new();
+    for ind in level.iter() {
+        let prefix = &ind.pairs[..ind.pairs.len() - 1];
+        prefix_blocks.entry((ind.dependent_table, ind.referenced_table, prefix)).or_default().push(ind);
+    }
+
+    let mut res = Vec::new();
+    for block in prefix_blocks.values() {
+        for a in block.iter() {
+            for b in block.iter() {
+                let (last_a, last_b) = (*a.pairs.last().unwrap(), *b.pairs.last().unwrap());
+                // dependent columns stay ordered, referenced columns must not repeat
+                if last_a.0 >= last_b.0 || a.pairs.iter().any(|p| p.1 == last_b.1) {
+                    continue;
+                }
+
+                let mut pairs = a.pairs.clone();
+                pairs.push(last_b);
+                let candidate = Candidate { dependent_table: a.dependent_table, referenced_table: a.referenced_table, pairs };
+
+                let apriori = (0..candidate.pairs.len()).all(|skip| {
+                    let mut pairs = candidate.pairs.clone();
+                    pairs.remove(skip);
+                    valid.contains(&Candidate { pairs, ..candidate.clone() })
+                });
+
+                if apriori {
+                    res.push(candidate);
+                }
+            }
+        }
+    }
+
+    res.sort_by(|a, b| (a.dependent_table, &a.pairs, a.referenced_table).cmp(&(b.dependent_table, &b.pairs, b.referenced_table)));
+    res
+}
+
+fn validate<'a>(tables: &'a [Table], candidate: &Candidate) -> bool {
+    let project = |table: &'a Table, columns: &Vec<usize>| -> Vec<Vec<&'a str>> {
+        (0..table.row_num())
+            .filter(|row| columns.iter().all(|c| !table.is_null(*row, *c)))
+            .map(|row| columns.iter().map(|c| table.value(row, *c)).collect::<Vec<_>>())
+            .collect()
+    };
+
+    let dependent = project(&tables[candidate.dependent_table], &candidate.pairs.iter().map(|p| p.0).collect());
+    let referenced: HashSet<Vec<&str>> = project(&tables[candidate.referenced_table], &candidate.pairs.iter().map(|p| p.1).collect())
+        .into_iter().collect();
+
+    dependent.iter().all(|values| referenced.contains(values))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dependency::result::AlgorithmResult;
+    use crate::testing;
+
+    fn tables() -> Vec<Table> {
+        let rows = |rows: &[&[&str]]| -> Vec<Vec<String>> { rows.iter().map(|row| row.iter().map(|v| v.to_string()).collect()).collect() };
+        vec![
+            testing::table("customers", &["id", "city"], &rows(&[&["1", "Paris"], &["2", "Rome"], &["3", "Oslo"]])),
+            testing::table("orders", &["order_no", "customer", "city"], &rows(&[&["10", "1", "Paris"], &["11", "2", "Rome"], &["12", "1", "Paris"], &["13", "", "Oslo"]]))
+        ]
+    }
+
+    fn rendered(res: &INDs) -> Vec<String> {
+        let mut inds: Vec<String> = res.dependencies().iter().map(|ind| ind.to_string()).collect();
+        inds.sort();
+        inds
+    }
+
+    #[test]
+    fn finds_unary_and_nary_inds() {
+        let res = Spider::new().execute_tables(&tables());
+        // the null customer of order 13 is ignored
+        assert_eq!(rendered(&res), vec![
+            "customers[city] ⊆ orders[city]",
+            "orders[city] ⊆ customers[city]",
+            "orders[customer, city] ⊆ customers[id, city]",
+            "orders[customer] ⊆ customers[id]"
+        ]);
+        assert_eq!(res.dependencies().iter().map(|ind| ind.arity()).max(), Some(2));
+    }
+
+    #[test]
+    fn stops_at_the_max_arity() {
+        let res = Spider::with_max_arity(1).execute_tables(&tables());
+        assert_eq!(rendered(&res), vec!["customers[city] ⊆ orders[city]", "orders[city] ⊆ customers[city]", "orders[customer] ⊆ customers[id]"]);
+    }
+
+    #[test]
+    fn pairs_that_break_together_are_no_nary_ind() {
+        // a ⊆ c and b ⊆ d hold, but the pair (1, y) is missing from the referenced rows
+        let rows = |rows: &[&[&str]]| -> Vec<Vec<String>> { rows.iter().map(|row| row.iter().map(|v| v.to_string()).collect()).collect() };
+        let left = testing::table("left", &["a", "b"], &rows(&[&["1", "x"], &["1", "y"]]));
+        let right = testing::table("right", &["c", "d"], &rows(&[&["1", "x"], &["2", "y"]]));
+        let res = Spider::new().execute_tables(&[left, right]);
+        assert_eq!(rendered(&res), vec!["left[a] ⊆ right[c]", "left[b] ⊆ right[d]", "right[d] ⊆ left[b]"]);
+    }
+}
@@ -1,14 +1,19 @@
 use crate::algorithm::Algorithm;
-use crate::frontend::partition::{Partitions, StrippedPartition};
+use crate::frontend::partition::{NullModel, Partitions, ProbeTable, StrippedPartition};
 use crate::frontend::table::Table;
-use crate::dependency::result::AlgorithmResult;
+use crate::dependency::result::Statistics;
 use crate::dependency::fd::FDs;
 
 use bit_set::BitSet;
+use rayon::prelude::*;
 use std::collections::HashMap;
+use std::time::Instant;
 
-use std::cell::RefCell;
-pub struct Tane;
+pub struct Tane {
+    max_error: f64,
+    parallel: bool,
+    null_model: NullModel
+}
 
 #[derive(Debug)]
 struct Combiantion {
@@ -29,16 +34,49 @@ impl Combiantion {
 
 impl Tane {
     pub fn new() -> Tane {
-        Tane {}
+        Tane { max_error: 0.0, parallel: false, null_model: NullModel::Equal }
+    }
+
+    // approximate mode: report every minimal fd whose g3 error is at most max_error
+    pub fn with_error(max_error: f64) -> Tane {
+        Tane { max_error, parallel: false, null_model: NullModel::Equal }
+    }
+
+    // spread the candidates of each level across the rayon thread pool, the fd set is the same as the sequential run
+    pub fn parallel(mut self) -> Tane {
+        self.parallel = true;
+        self
+    }
+
+    pub fn null_model(mut self, null_model: NullModel) -> Tane {
+        self.null_model = null_model;
+        self
+    }
+}
+
+impl Default for Tane {
+    fn default() -> Tane {
+        Tane::new()
     }
 }
 
 impl Algorithm for Tane {
-    fn execute(&mut self, table: &Table) -> Box<dyn AlgorithmResult> {
-        let mut res = FDs::new(&table);
+    type Result = FDs;
 
-        let partitions = Partitions::new(&table);
+    fn execute(&mut self, table: &Table) -> FDs {
+        self.mine(table)
+    }
+}
+
+impl Tane {
+    pub fn mine(&mut self, table: &Table) -> FDs {
+        let start = Instant::now();
+        let mut statistics = Statistics::default();
+        let mut res = FDs::new(table).with_null_model(self.null_model);
+
+        let partitions = Partitions::with_null_model(table, self.null_model);
         let attri_num = table.attributes.len();
+        let row_num = table.row_num();
 
         let mut level0 = HashMap::<BitSet, Combiantion>::new();
         let mut level1 = HashMap::<BitSet, Combiantion>::new();
@@ -46,7 +84,7 @@ impl Algorithm for Tane {
         // initial level0
         let level0_bitset: BitSet = (1..=attri_num).collect();
 
-        let level0_sp: StrippedPartition = StrippedPartition::new(attri_num);
+        let level0_sp: StrippedPartition = StrippedPartition::new(row_num);
         level0.insert(BitSet::new(), Combiantion {
             rhs: level0_bitset,
             partition: level0_sp,
@@ -61,7 +99,6 @@ impl Algorithm for Tane {
             let level1_bitset: BitSet = (1..=attri_num).collect();
 
             let level1_sp = partitions.0.get(attri).unwrap();
-            // println!("Attribute: {} <--> sp: {:?}", attri, level1_sp);
 
             level1.insert(level1_key, Combiantion {
                 rhs: level1_bitset,
@@ -70,30 +107,21 @@ impl Algorithm for Tane {
             });
         }
 
-        // for i in 0..(attri_num - 1) {
-        //     for j in (i + 1)..attri_num {
-        //         let attri_i = &table.attributes[i];
-        //         let attri_j = &table.attributes[j];
-        //         let mut key_i = BitSet::new();
-        //         key_i.insert(i + 1);
-        //         let mut key_j = BitSet::new();
-        //         key_j.insert(j + 1);
-        //         let sp_i = &level1.get(&key_i).unwrap().partition;
-        //         let sp_j = &level1.get(&key_j).unwrap().partition;
-        //         println!("Attribute: {} * {} <--> {:?} ", attri_i, attri_j, sp_i * sp_j);
-        //         println!("Attribute: {} * {} <--> {:?} ", attri_j, attri_i, sp_j * sp_i);
-        //     }
-        // }
+        let mut probe = ProbeTable::new(row_num);
 
         let mut l = 1;
         while !level1.is_empty() && l <= attri_num {
-            compute_dependencies(&mut level0, &mut level1, attri_num, &mut res);
-            level1 = prune(level1, attri_num, &mut res);
-            (level0, level1) = generate_next_level(level1);
+            let (candidates, before) = (level1.len(), res.len());
+            compute_dependencies(&mut level0, &mut level1, attri_num, row_num, self.max_error, self.parallel, &mut res);
+            level1 = prune(level1, attri_num, self.parallel, &mut res);
+            statistics.add_level(candidates, res.len() - before);
+            (level0, level1) = generate_next_level(level1, row_num, self.parallel, &mut probe);
             l += 1;
         }
 
-        Box::new(res)
+        statistics.elapsed = start.elapsed();
+        res.set_statistics(statistics);
+        res
     }
 }
 
@@ -104,7 +132,7 @@ fn initial_c_plus_for_level(level0: &mut HashMap::<BitSet, Combiantion>, level1:
         let mut X_clone = X.clone();
         for A in X {
             X_clone.remove(A);
-            let ref Cx_without_a = level0.get(&X_clone).unwrap().rhs;
+            let Cx_without_a = &level0.get(&X_clone).unwrap().rhs;
 
             Cx_without_A_list.push(Cx_without_a);
             X_clone.insert(A);
@@ -124,116 +152,156 @@ fn initial_c_plus_for_level(level0: &mut HashMap::<BitSet, Combiantion>, level1:
     }
 }
 
-fn compute_dependencies(level0: &mut HashMap::<BitSet, Combiantion>, level1: &mut HashMap::<BitSet, Combiantion>, attri_num: usize, res: &mut FDs) {
+fn compute_dependencies(level0: &mut HashMap::<BitSet, Combiantion>, level1: &mut HashMap::<BitSet, Combiantion>, attri_num: usize, row_num: usize, max_error: f64, parallel: bool, res: &mut FDs) {
     initial_c_plus_for_level(level0, level1, attri_num);
 
-    for (X, ch) in level1.iter_mut() {
-        if ch.is_valid() {
-            let ref mut c_plus = ch.rhs;
-            let mut intersection = X.clone();
+    let level0 = &*level0;
+    let found: Vec<(BitSet, usize, f64)> = if parallel {
+        level1.par_iter_mut()
+            .flat_map_iter(|(X, ch)| compute_dependencies_of(X, ch, level0, attri_num, row_num, max_error))
+            .collect()
+    } else {
+        level1.iter_mut()
+            .flat_map(|(X, ch)| compute_dependencies_of(X, ch, level0, attri_num, row_num, max_error))
+            .collect()
+    };
+
+    for (X_without_A, A, error) in found {
+        res.add_from_index(&X_without_A.iter().map(|x| x - 1).collect(), A - 1, error);
+    }
+}
 
-            intersection.intersect_with(c_plus);
+// checks X/A -> A for every A of X, only C+(X) is updated so the candidates of a level are independent
+fn compute_dependencies_of(X: &BitSet, ch: &mut Combiantion, level0: &HashMap::<BitSet, Combiantion>, attri_num: usize, row_num: usize, max_error: f64) -> Vec<(BitSet, usize, f64)> {
+    let mut found = Vec::new();
 
-            let mut X_clone = X.clone();
+    if ch.is_valid() {
+        let c_plus = &mut ch.rhs;
+        let mut intersection = X.clone();
 
-            for A in intersection.iter() {
-                X_clone.remove(A);
+        intersection.intersect_with(c_plus);
 
-                let ref spX_without_A = level0.get(&X_clone).unwrap().partition;
-                let ref spX = ch.partition;
+        let mut X_clone = X.clone();
 
-                if spX.get_error() == spX_without_A.get_error() {
-                    let X_without_A = X_clone.clone();
-                    // println!("X: {:?}, X/A: {:?} \t X/A: {:?}", spX, spX_without_A, X_without_A);
-                    res.add_from_index(&X_without_A.iter().map(|x| x - 1).collect(), A - 1);
+        for A in intersection.iter() {
+            X_clone.remove(A);
 
-                    ch.rhs.remove(A);
+            let spX_without_A = &level0.get(&X_clone).unwrap().partition;
+            let spX = &ch.partition;
 
-                    let mut R_without_X: BitSet = (1..=attri_num).collect();
+            if spX.get_error() == spX_without_A.get_error() {
+                let X_without_A = X_clone.clone();
+                found.push((X_without_A, A, 0.0));
 
-                    R_without_X.difference_with(X);
+                ch.rhs.remove(A);
 
-                    R_without_X.iter().for_each(|i| {ch.rhs.remove(i);});
-                }
+                let mut R_without_X: BitSet = (1..=attri_num).collect();
+
+                R_without_X.difference_with(X);
+
+                R_without_X.iter().for_each(|i| {ch.rhs.remove(i);});
+            } else if max_error > 0.0 {
+                // e(X/A) - e(X) is a lower bound of the g3 error, skip the exact computation when it is too large
+                let lower_bound = (spX_without_A.get_error() - spX.get_error()) as f64 / row_num as f64;
+                if lower_bound <= max_error {
+                    let error = spX_without_A.g3_error(spX, row_num);
+                    if error <= max_error {
+                        found.push((X_clone.clone(), A, error));
 
-                X_clone.insert(A);
+                        ch.rhs.remove(A);
+                    }
+                }
             }
+
+            X_clone.insert(A);
         }
     }
+
+    found
 }
 
-fn prune(level1: HashMap::<BitSet, Combiantion>, attri_num: usize, res: &mut FDs) -> HashMap::<BitSet, Combiantion> {
-    let level1_ptr = &level1 as *const HashMap<BitSet, Combiantion>;
-    let level1 = RefCell::new(level1);
+fn prune(mut level1: HashMap::<BitSet, Combiantion>, attri_num: usize, parallel: bool, res: &mut FDs) -> HashMap::<BitSet, Combiantion> {
+    // the key checks of X only read C+(X ∪ A / B) for A in X, which no other check removes,
+    // so they are computed on the unchanged level first and applied afterwards
+    let found: Vec<(BitSet, Vec<usize>)> = {
+        let level1 = &level1;
+        if parallel {
+            level1.par_iter().filter_map(|(x, ch)| prune_key(x, ch, level1, attri_num)).collect()
+        } else {
+            level1.iter().filter_map(|(x, ch)| prune_key(x, ch, level1, attri_num)).collect()
+        }
+    };
+
+    let element_to_remove: Vec<BitSet> = level1.iter()
+        .filter(|(_, ch)| ch.rhs.is_empty())
+        .map(|(x, _)| x.clone())
+        .collect();
+
+    for (x, rhs) in found {
+        let ch = level1.get_mut(&x).unwrap();
+        for a in rhs {
+            let lhs = x.clone();
+            res.add_from_index(&lhs.iter().map(|x| x - 1).collect(), a - 1, 0.0);
+            ch.rhs.remove(a);
+            ch.kill();
+        }
+    }
 
-    let mut element_to_remove = Vec::new();
+    for x in element_to_remove {
+        level1.remove(&x);
+    }
 
-    for (x, ch) in level1.borrow_mut().iter_mut() {
-        if ch.rhs.is_empty() {
-            element_to_remove.push(x.clone());
-            continue;
-        }
+    level1
+}
 
-        if ch.is_valid() && ch.partition.get_error() == 0 {
-            let mut rhs_without_x = ch.rhs.clone();
+// the rhs A of the fds X -> A found because X is a key
+fn prune_key(x: &BitSet, ch: &Combiantion, level1: &HashMap::<BitSet, Combiantion>, attri_num: usize) -> Option<(BitSet, Vec<usize>)> {
+    if ch.rhs.is_empty() || !ch.is_valid() || ch.partition.get_error() != 0 {
+        return None;
+    }
 
-            rhs_without_x.difference_with(x);
+    let mut found = Vec::new();
+    let mut rhs_without_x = ch.rhs.clone();
 
-            for a in rhs_without_x.iter() {
-                let mut intersect : BitSet = (1..=attri_num).collect();
+    rhs_without_x.difference_with(x);
 
-                let mut x_union_a_without_b = x.clone();
+    for a in rhs_without_x.iter() {
+        let mut intersect : BitSet = (1..=attri_num).collect();
 
-                x_union_a_without_b.insert(a);
+        let mut x_union_a_without_b = x.clone();
 
-                for b in x.iter() {
-                    x_union_a_without_b.remove(b);
-                    unsafe {
-                        if let Some(c) = (*level1_ptr).get(&x_union_a_without_b) {
-                            intersect.intersect_with(&c.rhs);
-                        } else {
-                            intersect = BitSet::new();
-                            break;
-                        }
-                    }
-                    x_union_a_without_b.insert(b);
-                }
+        x_union_a_without_b.insert(a);
 
-                if intersect.contains(a) {
-                    let lhs = x.clone();
-                    // println!("rhs/X: {:?}, inter: {:?} \t lhs: {:?}", rhs_without_x, intersect, lhs);
-                    res.add_from_index(&lhs.iter().map(|x| x - 1).collect(), a - 1);
-                    ch.rhs.remove(a);
-                    ch.kill();
-                }
+        for b in x.iter() {
+            x_union_a_without_b.remove(b);
+            if let Some(c) = level1.get(&x_union_a_without_b) {
+                intersect.intersect_with(&c.rhs);
+            } else {
+                intersect = BitSet::new();
+                break;
             }
+            x_union_a_without_b.insert(b);
         }
-    }
 
-    for x in element_to_remove {
-        level1.borrow_mut().remove(&x);
+        if intersect.contains(a) {
+            found.push(a);
+        }
     }
 
-    level1.into_inner()
+    if found.is_empty() { None } else { Some((x.clone(), found)) }
 }
 
-fn generate_next_level(level1: HashMap::<BitSet, Combiantion>) -> (HashMap::<BitSet, Combiantion>, HashMap::<BitSet, Combiantion>) {
+fn generate_next_level(level1: HashMap::<BitSet, Combiantion>, row_num: usize, parallel: bool, probe: &mut ProbeTable) -> (HashMap::<BitSet, Combiantion>, HashMap::<BitSet, Combiantion>) {
     let mut prefix_blocks: HashMap::<BitSet, Vec<BitSet>> = HashMap::new();
 
     level1.keys().for_each(|level_iter| {
         let mut prefix = level_iter.clone();
         prefix.remove(level_iter.iter().max().unwrap());
         
-        if prefix_blocks.contains_key(&prefix) {
-            prefix_blocks.get_mut(&prefix).unwrap().push(level_iter.clone());
-        } else {
-            let mut v = Vec::new();
-            v.push(level_iter.clone());
-            prefix_blocks.insert(prefix, v);
-        }
+        prefix_blocks.entry(prefix).or_default().push(level_iter.clone());
     });
 
-    let mut new_level = HashMap::new();
+    let mut candidates = Vec::new();
 
     for prefix_block_list in prefix_blocks.values() {
         if prefix_block_list.len() < 2 {
@@ -268,19 +336,141 @@ fn generate_next_level(level1: HashMap::<BitSet, Combiantion>) -> (HashMap::<Bit
             };
 
             if check {
-                if level1.get(&a).unwrap().is_valid() && level1.get(&b).unwrap().is_valid() {
-                    let st = &level1.get(&a).unwrap().partition * &level1.get(&b).unwrap().partition;
-                    new_level.insert(X, Combiantion { 
-                        rhs: BitSet::new(), partition: st, valid: true 
-                    });
-                } else {
-                    new_level.insert(X, Combiantion {
-                        rhs: BitSet::new(), partition: StrippedPartition::new(0), valid: false
-                    });
-                }
+                candidates.push((X, a, b));
             }
         }
     }
 
+    let combine = |probe: &mut ProbeTable, (X, a, b): (BitSet, BitSet, BitSet)| -> (BitSet, Combiantion) {
+        if level1.get(&a).unwrap().is_valid() && level1.get(&b).unwrap().is_valid() {
+            let st = probe.product(&level1.get(&a).unwrap().partition, &level1.get(&b).unwrap().partition);
+            (X, Combiantion {
+                rhs: BitSet::new(), partition: st, valid: true
+            })
+        } else {
+            (X, Combiantion {
+                rhs: BitSet::new(), partition: StrippedPartition::new(0), valid: false
+            })
+        }
+    };
+
+    // every worker thread gets its own probe table
+    let new_level = if parallel {
+        candidates.into_par_iter().map_init(|| ProbeTable::new(row_num), combine).collect()
+    } else {
+        candidates.into_iter().map(|candidate| combine(probe, candidate)).collect()
+    };
+
     (level1, new_level)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dependency::result::AlgorithmResult;
+    use crate::testing::{self, fd_set, Lcg};
+
+    fn table() -> Table {
+        let rows: Vec<Vec<String>> = [["1", "x", "k"], ["1", "x", "k"], ["1", "y", "k"], ["2", "z", "k"], ["2", "z", "k"]]
+            .iter().map(|row| row.iter().map(|v| v.to_string()).collect()).collect();
+        testing::table("g3", &["a", "b", "c"], &rows)
+    }
+
+    fn random_table(seed: u64) -> Table {
+        let mut rng = Lcg::new(seed);
+        let attri_num = 2 + rng.next(4) as usize;
+        let row_num = rng.next(30) as usize;
+        let domains: Vec<u64> = (0..attri_num).map(|_| 1 + rng.next(5)).collect();
+        testing::random_table(&mut rng, attri_num, row_num, |rng| domains.iter().map(|d| testing::value(rng.next(*d + 1))).collect())
+    }
+
+    fn rendered(fds: &FDs) -> Vec<(String, f64)> {
+        let mut found: Vec<_> = fds.iter().map(|fd| (fd.to_string(), fd.error())).collect();
+        found.sort_by(|a, b| a.0.cmp(&b.0));
+        found
+    }
+
+    #[test]
+    fn constant_columns_depend_on_nothing() {
+        let fds = Tane::new().mine(&table());
+        assert_eq!(rendered(&fds), vec![("{b} -> a".to_string(), 0.0), ("{} -> c".to_string(), 0.0)]);
+    }
+
+    #[test]
+    fn reports_the_g3_error() {
+        // dropping the y row makes a -> b hold, 1 of 5 rows. {} -> a needs 2 rows and {} -> b 3
+        let fds = Tane::with_error(0.25).mine(&table());
+        assert_eq!(rendered(&fds), vec![("{a} -> b".to_string(), 0.2), ("{b} -> a".to_string(), 0.0), ("{} -> c".to_string(), 0.0)]);
+
+        let fds = Tane::with_error(0.4).mine(&table());
+        assert_eq!(rendered(&fds), vec![("{a} -> b".to_string(), 0.2), ("{} -> a".to_string(), 0.4), ("{} -> c".to_string(), 0.0)]);
+    }
+
+    #[test]
+    fn distinct_nulls_determine_nothing() {
+        // the two null zips disagree on the city
+        let rows: Vec<Vec<String>> = [["1", "a"], ["1", "a"], ["", "b"], ["", "c"], ["2", ""], ["3", ""]]
+            .iter().map(|row| row.iter().map(|v| v.to_string()).collect()).collect();
+        let table = testing::table("places", &["zip", "city"], &rows);
+
+        // and the two null cities on the zip
+        let equal = Tane::new().null_model(NullModel::Equal).mine(&table);
+        assert!(rendered(&equal).is_empty());
+        // no two rows agree on a null, so both fds hold
+        let distinct = Tane::new().null_model(NullModel::Distinct).mine(&table);
+        assert_eq!(rendered(&distinct), vec![("{city} -> zip".to_string(), 0.0), ("{zip} -> city".to_string(), 0.0)]);
+        assert_eq!(distinct.null_model(), NullModel::Distinct);
+    }
+
+    #[test]
+    fn serializes_the_fds_with_their_statistics() {
+        let fds = Tane::with_error(0.25).mine(&table());
+        let value = serde_json::to_value(&fds).unwrap();
+
+        assert_eq!(value["table_name"], "g3");
+        assert_eq!(value["null_model"], "equal");
+        assert_eq!(value["attributes"][2], serde_json::json!({ "rank": 2, "value": "c" }));
+        let mut found: Vec<String> = value["fds"].as_array().unwrap().iter().map(|fd| {
+            let determinant: Vec<&str> = fd["determinant"].as_array().unwrap().iter().map(|a| a["value"].as_str().unwrap()).collect();
+            format!("{:?} -> {} ({})", determinant, fd["dependant"]["value"].as_str().unwrap(), fd["error"])
+        }).collect();
+        found.sort();
+        assert_eq!(found, vec!["[\"a\"] -> b (0.2)", "[\"b\"] -> a (0.0)", "[] -> c (0.0)"]);
+
+        // one entry per lattice level, {} -> c is found on the single attributes and the others on the pairs
+        let levels = value["statistics"]["levels"].as_array().unwrap();
+        assert_eq!(levels.len(), fds.statistics().levels.len());
+        assert_eq!(levels[0], serde_json::json!({ "level": 1, "candidates": 3, "found": 1 }));
+        assert_eq!(levels[1]["found"], 2);
+        assert_eq!(levels.iter().map(|level| level["found"].as_u64().unwrap()).sum::<u64>(), 3);
+        assert!(value["statistics"]["elapsed"]["nanos"].is_u64());
+    }
+
+    #[test]
+    fn no_error_is_exact() {
+        for seed in 0..40 {
+            let table = random_table(seed);
+            for null_model in [NullModel::Equal, NullModel::Distinct] {
+                let exact = Tane::new().null_model(null_model).mine(&table);
+                let approximate = Tane::with_error(0.0).null_model(null_model).mine(&table);
+                assert_eq!(fd_set(&approximate), fd_set(&exact), "seed {} ({})", seed, null_model);
+                assert!(approximate.iter().all(|fd| fd.error() == 0.0));
+            }
+        }
+    }
+
+    #[test]
+    fn parallel_levels_find_the_same_fds() {
+        for seed in 0..40 {
+            let table = random_table(seed);
+            for null_model in [NullModel::Equal, NullModel::Distinct] {
+                for max_error in [0.0, 0.1, 0.3] {
+                    let sequential = Tane::with_error(max_error).null_model(null_model).mine(&table);
+                    let parallel = Tane::with_error(max_error).parallel().null_model(null_model).mine(&table);
+                    assert_eq!(fd_set(&parallel), fd_set(&sequential), "seed {} ({}, {})", seed, null_model, max_error);
+                }
+                assert_eq!(fd_set(&Tane::new().parallel().null_model(null_model).mine(&table)), fd_set(&Tane::new().null_model(null_model).mine(&table)));
+            }
+        }
+    }
+}
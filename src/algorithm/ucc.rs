@@ -0,0 +1,167 @@
+use crate::algorithm::Algorithm;
+use crate::frontend::partition::{NullModel, Partitions, ProbeTable, StrippedPartition};
+use crate::frontend::table::Table;
+use crate::dependency::result::Statistics;
+use crate::dependency::ucc::UCCs;
+
+use bit_set::BitSet;
+use std::collections::HashMap;
+use std::time::Instant;
+
+// level-wise discovery of the minimal unique column combinations
+pub struct Ucc {
+    null_model: NullModel
+}
+
+impl Ucc {
+    pub fn new() -> Ucc {
+        Ucc { null_model: NullModel::Equal }
+    }
+
+    pub fn null_model(mut self, null_model: NullModel) -> Ucc {
+        self.null_model = null_model;
+        self
+    }
+}
+
+impl Default for Ucc {
+    fn default() -> Ucc {
+        Ucc::new()
+    }
+}
+
+impl Algorithm for Ucc {
+    type Result = UCCs;
+
+    fn execute(&mut self, table: &Table) -> UCCs {
+        let start = Instant::now();
+        let mut statistics = Statistics::default();
+        let mut res = UCCs::new(table).with_null_model(self.null_model);
+
+        let partitions = Partitions::with_null_model(table, self.null_model);
+
+        // only the non unique combinations are kept, every superset of a key is pruned
+        let mut level = HashMap::<BitSet, StrippedPartition>::new();
+        for (index, attri) in table.attributes.iter().enumerate() {
+            let mut key = BitSet::new();
+            key.insert(index);
+
+            let sp = partitions.0.get(attri).unwrap();
+            if sp.is_unique() {
+                res.add_from_index(&key);
+            } else {
+                level.insert(key, sp.clone());
+            }
+        }
+        statistics.add_level(table.attributes.len(), res.len());
+
+        let mut probe = ProbeTable::new(table.row_num());
+        while !level.is_empty() {
+            let before = res.len();
+            let (next, candidates) = generate_next_level(&level, &mut probe, &mut res);
+            if candidates > 0 {
+                statistics.add_level(candidates, res.len() - before);
+            }
+            level = next;
+        }
+
+        statistics.elapsed = start.elapsed();
+        res.set_statistics(statistics);
+        res
+    }
+}
+
+// the next level and the number of minimal candidates checked
+fn generate_next_level(level: &HashMap<BitSet, StrippedPartition>, probe: &mut ProbeTable, res: &mut UCCs) -> (HashMap<BitSet, StrippedPartition>, usize) {
+    let mut prefix_blocks: HashMap<BitSet, Vec<&BitSet>> = HashMap::new();
+
+    for x in level.keys() {
+        let mut prefix = x.clone();
+        prefix.remove(x.iter().max().unwrap());
+        prefix_blocks.entry(prefix).or_insert_with(Vec::new).push(x);
+    }
+
+    let mut new_level = HashMap::new();
+    let mut candidates = 0;
+
+    for block in prefix_blocks.values() {
+        for i in 0..block.len() {
+            for j in (i + 1)..block.len() {
+                let mut x = block[i].clone();
+                x.union_with(block[j]);
+
+                // a subset missing from the level is unique, so x would not be minimal
+                let minimal = x.iter().all(|a| {
+                    let mut subset = x.clone();
+                    subset.remove(a);
+                    level.contains_key(&subset)
+                });
+
+                if !minimal {
+                    continue;
+                }
+
+                candidates += 1;
+                let sp = probe.product(level.get(block[i]).unwrap(), level.get(block[j]).unwrap());
+                if sp.is_unique() {
+                    res.add_from_index(&x);
+                } else {
+                    new_level.insert(x, sp);
+                }
+            }
+        }
+    }
+
+    (new_level, candidates)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dependency::result::AlgorithmResult;
+    use crate::testing::{self, Lcg};
+
+    fn uccs(res: &UCCs) -> Vec<Vec<usize>> {
+        let mut uccs: Vec<Vec<usize>> = res.dependencies().iter().map(|ucc| ucc.0.0.iter().map(|a| a.rank).collect()).collect();
+        uccs.sort();
+        uccs
+    }
+
+    // every column combination whose rows differ pairwise, without a unique subset
+    fn brute_force(table: &Table, null_model: NullModel) -> Vec<Vec<usize>> {
+        let attri_num = table.attributes.len();
+        let agree = |r: usize, s: usize, c: usize| table.code(r, c) == table.code(s, c) && (null_model == NullModel::Equal || !table.is_null(r, c));
+        let unique = |mask: usize| (0..table.row_num()).all(|r| (r + 1..table.row_num()).all(|s| (0..attri_num).any(|c| mask & 1 << c != 0 && !agree(r, s, c))));
+        let keys: Vec<usize> = (1..1usize << attri_num).filter(|mask| unique(*mask)).collect();
+        let mut uccs: Vec<Vec<usize>> = keys.iter()
+            .filter(|mask| !keys.iter().any(|other| *other != **mask && *other & **mask == *other))
+            .map(|mask| (0..attri_num).filter(|c| mask & 1 << c != 0).collect())
+            .collect();
+        uccs.sort();
+        uccs
+    }
+
+    #[test]
+    fn finds_the_minimal_keys() {
+        let rows: Vec<Vec<String>> = [["1", "a", "x"], ["2", "a", "y"], ["3", "b", "x"], ["", "b", "y"], ["", "c", "x"]]
+            .iter().map(|row| row.iter().map(|v| v.to_string()).collect()).collect();
+        let table = testing::table("people", &["id", "name", "city"], &rows);
+
+        // the two nulls of id agree
+        assert_eq!(uccs(&Ucc::new().execute(&table)), vec![vec![0, 1], vec![0, 2], vec![1, 2]]);
+        assert_eq!(uccs(&Ucc::new().null_model(NullModel::Distinct).execute(&table)), vec![vec![0], vec![1, 2]]);
+    }
+
+    #[test]
+    fn same_uccs_as_brute_force() {
+        for seed in 0..60 {
+            let mut rng = Lcg::new(seed);
+            let (attri_num, row_num) = (2 + rng.next(4) as usize, rng.next(20) as usize);
+            let domains: Vec<u64> = (0..attri_num).map(|_| 2 + rng.next(8)).collect();
+            let table = testing::random_table(&mut rng, attri_num, row_num, |rng| domains.iter().map(|d| testing::value(rng.next(*d + 1))).collect());
+            for null_model in [NullModel::Equal, NullModel::Distinct] {
+                assert_eq!(uccs(&Ucc::new().null_model(null_model).execute(&table)), brute_force(&table, null_model), "seed {} ({})", seed, null_model);
+            }
+        }
+    }
+}
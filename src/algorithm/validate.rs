@@ -0,0 +1,177 @@
+use crate::algorithm::Algorithm;
+use crate::frontend::partition::{NullModel, Partitions, ProbeTable, StrippedPartition};
+use crate::frontend::table::Table;
+use crate::dependency::fd::FunctionalDependency;
+use crate::dependency::result::Statistics;
+use crate::dependency::validation::{FdValidation, Validations, ViolatingGroup};
+
+use std::collections::HashMap;
+use std::time::Instant;
+
+// checks given fds on a table, the fds may come from a miner or be declared by hand
+pub struct Validator {
+    fds: Vec<FunctionalDependency>,
+    null_model: NullModel,
+    max_groups: Option<usize>
+}
+
+// the dependant value of a row, nulls only agree with each other under null = null
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum Value {
+    Code(u32),
+    Null,
+    NullAt(usize)
+}
+
+impl Validator {
+    pub fn new(fds: Vec<FunctionalDependency>) -> Validator {
+        Validator { fds, null_model: NullModel::Equal, max_groups: None }
+    }
+
+    pub fn null_model(mut self, null_model: NullModel) -> Validator {
+        self.null_model = null_model;
+        self
+    }
+
+    // keep only the first groups of every fd, the number of all the groups is still reported
+    pub fn max_groups(mut self, max_groups: usize) -> Validator {
+        self.max_groups = Some(max_groups);
+        self
+    }
+
+    fn value(&self, table: &Table, row: usize, col: usize) -> Value {
+        if !table.is_null(row, col) {
+            Value::Code(table.code(row, col))
+        } else if self.null_model == NullModel::Equal {
+            Value::Null
+        } else {
+            Value::NullAt(row)
+        }
+    }
+
+    // every class of the determinant partition is split by the dependant value,
+    // a class with more than one part is a violating group
+    fn validate(&self, table: &Table, fd: &FunctionalDependency, partition: &StrippedPartition) -> FdValidation {
+        let row_num = table.row_num();
+        let col = fd.dependant_rank();
+        let determinant: Vec<usize> = fd.disintegrate().0.iter().map(|a| a.rank).collect();
+
+        let mut removed = 0;
+        let mut pairs = 0u128;
+        let mut violations = Vec::new();
+        let mut violating_groups = 0;
+
+        for eq_class in partition.clusters() {
+            let mut parts: Vec<(Value, Vec<usize>)> = Vec::new();
+            let mut index = HashMap::new();
+            for row in eq_class.iter().map(|row| *row as usize) {
+                let value = self.value(table, row, col);
+                let part = *index.entry(value).or_insert_with(|| {
+                    parts.push((value, Vec::new()));
+                    parts.len() - 1
+                });
+                parts[part].1.push(row);
+            }
+            if parts.len() == 1 {
+                continue;
+            }
+
+            let largest = parts.iter().map(|(_, rows)| rows.len()).max().unwrap();
+            removed += eq_class.len() - largest;
+            let agreeing: u128 = parts.iter().map(|(_, rows)| (rows.len() as u128).pow(2)).sum();
+            pairs += (eq_class.len() as u128).pow(2) - agreeing;
+
+            violating_groups += 1;
+            if self.max_groups.is_none_or(|max| violations.len() < max) {
+                let first = eq_class[0] as usize;
+                violations.push(ViolatingGroup {
+                    determinant: determinant.iter().map(|a| table.value(first, *a).to_string()).collect(),
+                    dependant: parts.into_iter().map(|(_, rows)| (table.value(rows[0], col).to_string(), rows)).collect()
+                });
+            }
+        }
+
+        let (g3, g1) = if row_num == 0 {
+            (0.0, 0.0)
+        } else {
+            (removed as f64 / row_num as f64, pairs as f64 / (row_num as f64 * row_num as f64))
+        };
+
+        FdValidation { fd: fd.clone(), holds: violating_groups == 0, g3, g1, violating_groups, violations }
+    }
+}
+
+impl Algorithm for Validator {
+    type Result = Validations;
+
+    fn execute(&mut self, table: &Table) -> Validations {
+        let start = Instant::now();
+        let mut statistics = Statistics::default();
+        let mut res = Validations::new(table).with_null_model(self.null_model);
+
+        let partitions = Partitions::with_null_model(table, self.null_model);
+        let mut probe = ProbeTable::new(table.row_num());
+
+        // fds sharing a determinant share its partition
+        let mut determinants = HashMap::new();
+        for fd in self.fds.iter() {
+            let x = fd.determinant_set();
+            let partition = determinants.entry(x.clone()).or_insert_with(|| {
+                x.iter().fold(StrippedPartition::new(table.row_num()), |partition, col| {
+                    probe.product(&partition, partitions.0.get(&table.attributes[col]).unwrap())
+                })
+            });
+            res.add(self.validate(table, fd, partition));
+        }
+
+        let held = res.iter().filter(|v| v.holds).count();
+        statistics.add_level(self.fds.len(), held);
+        statistics.elapsed = start.elapsed();
+        res.set_statistics(statistics);
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frontend::table::Attribute;
+    use crate::dependency::result::AlgorithmResult;
+
+    fn table() -> Table {
+        let metadata = ["zip", "city"].iter().enumerate().map(|(rank, name)| Attribute::new(rank, name.to_string())).collect();
+        let mut table = Table::with_null_tokens("places".to_string(), metadata, vec![String::new()]);
+        for row in [["1", "a"], ["1", "a"], ["1", "b"], ["2", "c"], ["", "d"], ["", "e"]] {
+            table.add_entry(row.iter().map(|v| v.to_string()).collect());
+        }
+        table
+    }
+
+    fn validate(fd: &str, null_model: NullModel) -> Validations {
+        let table = table();
+        let fds = FunctionalDependency::parse(fd, &table.attributes).unwrap();
+        Validator::new(fds).null_model(null_model).execute(&table)
+    }
+
+    #[test]
+    fn reports_violating_groups() {
+        let res = validate("zip -> city", NullModel::Equal);
+        let zip = &res.dependencies()[0];
+        assert!(!zip.holds);
+        assert_eq!(zip.violating_groups, 2);
+        assert_eq!(zip.violations[0].dependant, vec![("a".to_string(), vec![0, 1]), ("b".to_string(), vec![2])]);
+        assert_eq!(zip.violations[1].rows(), vec![4, 5]);
+        assert!((zip.g3 - 2.0 / 6.0).abs() < 1e-9);
+        assert!((zip.g1 - 6.0 / 36.0).abs() < 1e-9);
+
+        assert!(validate("city -> zip", NullModel::Equal).all_hold());
+    }
+
+    #[test]
+    fn distinct_nulls_determine_nothing() {
+        let res = validate("zip -> city", NullModel::Distinct);
+        let zip = &res.dependencies()[0];
+        assert_eq!(zip.violating_groups, 1);
+        assert_eq!(zip.violations[0].rows(), vec![0, 1, 2]);
+    }
+}
@@ -1,54 +1,52 @@
 use crate::dependency::fd::*;
+use crate::dependency::workspace::Workspace;
+use crate::dependency::syntax::{parse_fd, parse_names};
 use crate::frontend::table::Attribute;
+use crate::dependency::result::write_markdown;
 use serde::{Deserialize, Serialize};
-use lazy_static::lazy_static;
-use regex::Regex;
 use bit_set::BitSet;
 use std::fmt::{Formatter, Display};
-use std::io::{BufReader, BufWriter, Read, Write, stdin, BufRead};
-use std::fs::{File, OpenOptions};
+use std::io::{BufReader, stdin, BufRead};
+use std::fs::OpenOptions;
+use std::rc::Rc;
 
-#[derive(Deserialize, Serialize)]
-struct FDs(Vec<FD>, String);
+const ATTRIBUTES: &str = "attributes";
 
-#[derive(Deserialize, Serialize)]
-struct AttributesSink(Attributes);
+// a named collection of fds in the workspace, the commands use the mined and the new one
+#[derive(Default, Deserialize, Serialize)]
+struct Collection(Vec<FD>);
 
-impl AttributesSink {
-    fn new() -> AttributesSink {
-        let f = if let Ok(f) = File::open("attributes.json") {
-            f
-        } else {
-            File::create("attributes.json").unwrap()
-        };
-
-        let mut reader = BufReader::new(f);
-        let mut load = String::new();
+impl Collection {
+    fn load(workspace: &Workspace, name: &str) -> Result<Collection, String> {
+        let collection = workspace.load(name).map_err(|e| format!("Can not load {} from {}: {}", name, workspace.dir().display(), e))?;
+        Ok(collection.unwrap_or_default())
+    }
 
-        if let Ok(_) = reader.read_to_string(&mut load) {
-            log::debug!("Already exit {}", load);
-        } else {
-            log::warn!("Empty file");
+    fn save(&self, workspace: &Workspace, name: &str) -> Result<(), String> {
+        for fd in self.0.iter() {
+            log::trace!("Save fd: {}", fd);
         }
+        workspace.save(name, self).map_err(|e| format!("Can not save {} into {}: {}", name, workspace.dir().display(), e))
+    }
+}
 
-        let attributes: Attributes = if let Ok(attributes) = serde_json::from_str(load.as_str()) {
-            attributes
-        } else {
-            log::warn!("Attributes Load Error");
-            Attributes(Vec::new())
-        };
+#[derive(Deserialize, Serialize)]
+struct AttributesSink(Attributes);
 
-        AttributesSink(attributes)
+impl AttributesSink {
+    fn load(workspace: &Workspace) -> Result<AttributesSink, String> {
+        let attributes = workspace.load(ATTRIBUTES)
+            .map_err(|e| format!("Can not load {} from {}: {}", ATTRIBUTES, workspace.dir().display(), e))?
+            .unwrap_or_else(|| {
+                log::warn!("No attributes in the workspace {}", workspace.dir().display());
+                Attributes(Vec::new())
+            });
+
+        Ok(AttributesSink(attributes))
     }
 
-    fn build_attributes(&self, index: usize) -> Attribute {
-        if let Some(attribute) = self.0.0.get(index) {
-            assert_eq!(attribute.rank, index);
-            attribute.clone()
-        } else {
-            log::error!("Can not find the {} in the attributes set", index);
-            panic!("attributes error");
-        }
+    fn save(&self, workspace: &Workspace) -> Result<(), String> {
+        workspace.save(ATTRIBUTES, &self.0).map_err(|e| format!("Can not save {} into {}: {}", ATTRIBUTES, workspace.dir().display(), e))
     }
 
     fn clear(&mut self) {
@@ -56,23 +54,6 @@ impl AttributesSink {
     }
 }
 
-impl Drop for AttributesSink {
-    fn drop(&mut self) {
-        let s = serde_json::to_string(&self).unwrap();
-
-        log::trace!("Save: {}", s);
-
-        let f = OpenOptions::new()
-            .write(true).truncate(true)
-            .open("attributes.json").unwrap();
-
-        let mut wirter = BufWriter::new(f);
-
-        wirter.write(s.as_bytes()).unwrap();
-
-    }
-}
-
 #[derive(Deserialize, Serialize)]
 struct FD {
     determinant: Attributes,
@@ -123,131 +104,52 @@ impl FD {
         Self::distance(fd1, fd2, 1.0, (n - 1.0) / (n * n), 1.0 / (n * n))
     }
 
-    pub fn r_neighborhood_cnt(&self, n: usize, r: f64) -> usize {
-        fn dfs(index: usize, n: usize, deter_set: &mut BitSet, x1: &BitSet, y1: &BitSet, r: f64) -> usize {
-            let mut cnt = 0;
-            if index < n {
-                cnt += dfs(index + 1, n, deter_set, x1, y1, r);
-                deter_set.insert(index);
-                cnt += dfs(index + 1, n, deter_set, x1, y1, r);
-                deter_set.remove(index);
-            } else {
-
-                let mut determinant_set = deter_set.clone();
-                let mut dependant_set = BitSet::new();
-
-                for idx in deter_set.iter() {
-                    determinant_set.remove(idx);
-                    dependant_set.insert(idx);
-
-                    let distance = |x1: &BitSet, y1: &BitSet, x2: &BitSet, y2: &BitSet| {
-                        let s1: BitSet = x1.union(&y1).collect();
-                        let s2: BitSet = x2.union(&y2).collect();
-
-                        let cnt1 = s1.symmetric_difference(&s2).collect::<BitSet>().len() as f64;
-                        let cnt2 = x1.symmetric_difference(&x2).collect::<BitSet>().len() as f64;
-                        let cnt3 = y1.symmetric_difference(&y2).collect::<BitSet>().len() as f64;
-
-                        let n = n as f64;
-
-                        let delta1 = 1.0;
-                        let delta2 = (n - 1.0) / (n * n);
-                        let delta3 = 1.0 / (n * n);
-
-                        delta1 * cnt1 + delta2 * cnt2 + delta3 * cnt3
-                    };
-
-                    if distance(&x1, &y1, &determinant_set, &dependant_set) < r {
-                        cnt += 1;
-                    }
-                }
-            }
-    
-            cnt
-        }
-        
-        let mut deter_set = BitSet::new();
-
+    pub fn r_neighborhood_cnt(&self, n: usize, r: f64) -> u128 {
         let x1: BitSet = self.determinant.0.iter().map(|a| a.rank).collect();
         let y1: BitSet = self.dependant.0.iter().map(|a| a.rank).collect();
 
-        dfs(0, n, &mut deter_set, &x1, &y1, r)
+        r_neighborhood_size(&x1, &y1, n, r)
     }
 }
 
-impl FDs {
-    fn new(filename: &str) -> FDs {
-        let f = if let Ok(f) = File::open(filename) {
-            f
-        } else {
-            File::create(filename).unwrap()
-        };
-
-        let mut reader = BufReader::new(f);
-        let mut load = String::new();
-
-        if let Ok(_) = reader.read_to_string(&mut load) {
-            log::debug!("Already exit {}", load);
-        } else {
-            log::debug!("Empty file");
-        }
+// where the fds or attributes of a command are read from
+pub enum Source {
+    Args(Vec<String>),
+    File(String),
+    Stdin
+}
 
-        let mut fds: FDs = if let Ok(fds) = serde_json::from_str(load.as_str()) {
-            // fds.1 = String::from(s.clone());
-            fds
-        } else {
-            FDs(Vec::new(), String::from(filename.clone()))
+impl Source {
+    // one fd per line with its index, blank lines are skipped
+    pub fn lines(&self) -> Result<Vec<(usize, String)>, String> {
+        let lines: Vec<String> = match self {
+            Source::Args(args) => args.clone(),
+            Source::File(path) => {
+                let file = OpenOptions::new().read(true).open(path).map_err(|e| format!("Can not open {}: {}", path, e))?;
+                BufReader::new(file).lines().collect::<Result<_, _>>().map_err(|e| format!("Can not read {}: {}", path, e))?
+            }
+            Source::Stdin => stdin().lock().lines().collect::<Result<_, _>>().map_err(|e| format!("Can not read the standard input: {}", e))?
         };
-
-        fds.1 = String::from(filename);
-
-        fds
-    }
-
-    fn mined() -> FDs {
-        Self::new("mined.json")
-    }
-
-    fn new_fd() -> FDs {
-        Self::new("new.json")
-    }
-
-    fn clear(&mut self) {
-        self.0.clear();
+        Ok(lines.into_iter().enumerate().filter(|(_, line)| !line.trim().is_empty()).collect())
     }
 }
 
-impl Drop for FDs {
-    fn drop(&mut self) {
-        for fd in self.0.iter() {
-            log::trace!("Save fd: {}", fd);
-        }
-        let s = serde_json::to_string(&self).unwrap();
-
-        let filepath = self.1.as_str();
-
-        log::trace!("Save: {}", s);
-
-        let f = OpenOptions::new()
-            .write(true).truncate(true)
-            .open(filepath).unwrap();
-
-        let mut wirter = BufWriter::new(f);
-
-        wirter.write(s.as_bytes()).unwrap();
-
-    }
+pub enum AttributesSource {
+    Names(Vec<String>),
+    // the header row of a csv file
+    Csv(String),
+    // the first line, the names are separated by commas as in the fds
+    Stdin
 }
 
 pub enum Action {
-    Add,
+    Add(Source),
     Clear,
-    Load,
+    Load(Source),
     Display,
-    Single,
+    Single(Source),
     Current,
-    Attributes,
-    Nothing
+    Attributes(AttributesSource)
 }
 
 pub enum Type {
@@ -256,232 +158,234 @@ pub enum Type {
     Attributes
 }
 
-pub enum Output {
-    Std,
+impl Action {
+    // these commands only read the workspace, so they can run next to each other
+    pub fn is_read_only(&self) -> bool {
+        matches!(self, Action::Display | Action::Single(_) | Action::Current)
+    }
 }
 
-
-
-pub fn analysis_cli(action: Action, fd_type: Type, n: usize, r: f64) {
-
-    fn parser_fd(fd_str: &String, attribute_sink: &AttributesSink) -> FD {
-        lazy_static!{
-            static ref REGEX: Regex = Regex::new(
-                r##"(?P<l>[\t 0-9,]+)->(?P<r>[\t 0-9,]+)"##
-            ).unwrap();
-
-            static ref NUM: Regex = Regex::new(
-                r##"([[:digit:]]+)"##
-            ).unwrap();
+impl Type {
+    // the name of the collection in the workspace
+    fn collection(&self) -> &'static str {
+        match self {
+            Type::Mined => "mined",
+            Type::New => "new",
+            Type::Attributes => ATTRIBUTES
         }
+    }
+}
 
-        let (determinant, dependant) = {
-            if let Some(cap) = REGEX.captures(fd_str.as_str()) {
-                if let (Some(determinant), Some(dependant)) = (cap.name("l"), cap.name("r")) {
-                    (determinant.as_str(), dependant.as_str())
-                } else {
-                    log::error!("Capture lost at {}", fd_str);
-                    panic!("Capture lost!");
-                }
-            } else {
-                log::error!("Capture lost at {}", fd_str);
-                panic!("Capture lost!");
-            }
-        };
-
-        let mut determinant_vec = Vec::new();
+pub enum Output {
+    Std,
+    Mdfile
+}
 
-        let mut dependant_vec = Vec::new();
+// the r-neighbourhood of an fd of size n and the share of it taken by the mined fds
+pub struct AnalysisRow {
+    pub fd: String,
+    pub n: u128,
+    pub mn: usize,
+    pub error: f64
+}
 
-        for cap in NUM.captures_iter(determinant) {
-            let num = cap[0].to_string().parse::<usize>().unwrap();
-            let attr = attribute_sink.build_attributes(num);
-            determinant_vec.push(attr);
-        }
+impl Display for AnalysisRow {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "FD: {} has N: {}, MN: {}, error: %{}", self.fd, self.n, self.mn, self.error)
+    }
+}
 
-        for cap in NUM.captures_iter(dependant) {
-            let num = cap[0].to_string().parse::<usize>().unwrap();
-            let attr = attribute_sink.build_attributes(num);
-            dependant_vec.push(attr);
+// markdown report written into the workspace by the analysis commands when asked for an mdfile
+const ANALYSIS_MDFILE: &str = "analysis.md";
+
+// the lines the command reports, the caller shows them
+pub fn analysis_cli(workspace: &Workspace, action: Action, fd_type: Type, n: usize, r: f64, output: Output) -> Result<Vec<String>, String> {
+
+    // every line is parsed before anything is changed, the errors point at the line and column
+    fn parse_lines(source: &Source, attribute_sink: &AttributesSink) -> Result<Vec<FD>, String> {
+        let mut fds = Vec::new();
+        let mut errors = Vec::new();
+        for (index, line) in source.lines()? {
+            log::info!("Get str {}", line);
+            match parse_fd(&line).and_then(|parsed| parsed.resolve(&attribute_sink.0.0)) {
+                Ok((determinant, dependant)) => fds.push(FD::new_from_vec(determinant, dependant)),
+                Err(e) => errors.push(format!("line {}: {}", index + 1, e.report(&line)))
+            }
         }
-
-        let fd = FD::new_from_vec(determinant_vec, dependant_vec);
-        fd
+        if errors.is_empty() { Ok(fds) } else { Err(errors.join("\n")) }
     }
-   
-    match action {
 
-        Action::Add | Action::Single => {
-            let mut fd_str = String::new();
-            println!("Please input the fd as format of (index)* -> (index)*");
-            stdin().read_line(&mut fd_str).unwrap();
-
-            log::info!("Get str {}", fd_str);
-
-            let attribute_sink = AttributesSink::new();
+    let mut report = Vec::new();
+    match action {
 
-            let fd = parser_fd(&fd_str, &attribute_sink);
+        Action::Add(source) => {
+            if let Type::Attributes = fd_type {
+                return Err("Can not add single Attribute".to_string());
+            }
 
-            if let Action::Add = action {
-                match fd_type {
-                    Type::Mined => {
-                        save_mined_fd(fd);
-                    }
-                    Type::New => {
-                        save_new_fd(fd);
-                    }
-                    Type::Attributes => {
-                        println!("Can not add single Attribute");
+            let attribute_sink = AttributesSink::load(workspace)?;
+            let mined = mined_fd_set(workspace, &attribute_sink)?;
+            let mut fds = Collection::load(workspace, fd_type.collection())?;
+
+            for fd in parse_lines(&source, &attribute_sink)? {
+                if let Type::New = fd_type {
+                    let implied = fd.dependant.0.iter().all(|a| {
+                        mined.closure(&fd.determinant.0.iter().map(|b| b.rank).collect()).contains(a.rank)
+                    });
+                    if implied {
+                        report.push(format!("The fd {} is implied by the mined fds", fd));
+                    } else {
+                        report.push(format!("The fd {} is not implied by the mined fds", fd));
                     }
                 }
-            } else {
-                analysis_one_and_output(&fd, n, r);
+                fds.0.push(fd);
             }
-
+            fds.save(workspace, fd_type.collection())?;
         }
-        Action::Load => {
-            println!("Enter the load file of the format (index*) -> (index*)");
-            let mut file = String::new();
-            stdin().read_line(&mut file).unwrap();
-            let file = file.trim().to_string();
-            let attribute_sink = AttributesSink::new();
-            let file = OpenOptions::new().read(true).open(file).unwrap();
-            let reader = BufReader::new(file);
-
-            for line in reader.lines() {
-                if let Ok(line) = line {
-                    log::debug!("{}", line);
-                    let fd = parser_fd(&line, &attribute_sink);
-                    log::debug!("Load FD: {}", fd);
-                    match fd_type {
-                        Type::Mined => {
-                            save_mined_fd(fd);
-                        }
-                        Type::New => {
-                            save_new_fd(fd);
-                        }
-                        Type::Attributes => {
-                            println!("Can not load single Attribute");
-                        }
-                    }
-                }
+        Action::Single(source) => {
+            let attribute_sink = AttributesSink::load(workspace)?;
+            let mined = Collection::load(workspace, Type::Mined.collection())?;
+            let rows = parse_lines(&source, &attribute_sink)?.iter().map(|fd| analysis_row(fd, &mined, n, r)).collect();
+            report.extend(output_rows(workspace, rows, &output)?);
+        }
+        Action::Load(source) => {
+            if let Type::Attributes = fd_type {
+                return Err("Can not load single Attribute".to_string());
             }
+            let attribute_sink = AttributesSink::load(workspace)?;
+
+            let mut fds = Collection::load(workspace, fd_type.collection())?;
+            for fd in parse_lines(&source, &attribute_sink)? {
+                log::debug!("Load FD: {}", fd);
+                fds.0.push(fd);
+            }
+            fds.save(workspace, fd_type.collection())?;
 
         }
         Action::Clear => {
             match fd_type {
-                Type::Mined => {
-                    let mut fds = FDs::mined();
-                    fds.clear();
-                }
-                Type::New => {
-                    let mut fds = FDs::new_fd();
-                    fds.clear();
+                Type::Mined | Type::New => {
+                    Collection::default().save(workspace, fd_type.collection())?;
                 }
                 Type::Attributes => {
-                    let mut attr = AttributesSink::new();
+                    let mut attr = AttributesSink::load(workspace)?;
                     attr.clear();
+                    attr.save(workspace)?;
                 }
             }
         }
         Action::Display => {
-            analysis_new_and_output(n, r);
+            let new_fd = Collection::load(workspace, Type::New.collection())?;
+            let mined = Collection::load(workspace, Type::Mined.collection())?;
+            let rows = new_fd.0.iter().map(|fd| analysis_row(fd, &mined, n, r)).collect();
+            report.extend(output_rows(workspace, rows, &output)?);
         }
 
         Action::Current => {
 
             if let Type::Attributes = fd_type {
-                let attribute = AttributesSink::new();
-                println!("The current attributes is: {}", attribute.0);
+                let attribute = AttributesSink::load(workspace)?;
+                if let Output::Mdfile = output {
+                    let rows = attribute.0.0.iter().map(|a| vec![a.rank.to_string(), a.value.clone()]).collect();
+                    report.push(save_markdown(workspace, "Current attributes", &["Rank", "Attribute"], rows)?);
+                } else {
+                    report.push(format!("The current attributes is: {}", attribute.0));
+                }
             } else {
-                let fds = match fd_type {
-                    Type::Mined => {
-                        FDs::mined()
-                    }
-                    Type::New => {
-                        FDs::new_fd()
-                    }
-                    _ => {
-                        panic!("Bad trace");
-                    }
-                };
+                let fds = Collection::load(workspace, fd_type.collection())?;
     
-                for fd in fds.0.iter() {
-                    println!("{}", fd);
+                if let Output::Mdfile = output {
+                    let rows = fds.0.iter().map(|fd| vec![fd.determinant.to_string(), fd.dependant.to_string()]).collect();
+                    report.push(save_markdown(workspace, &format!("Current fds of {}", fd_type.collection()), &["Determinant", "Dependant"], rows)?);
+                } else {
+                    report.extend(fds.0.iter().map(|fd| fd.to_string()));
                 }
             }
 
         }
 
-        Action::Attributes => {
-            let mut attribute_sink = AttributesSink::new();
-            lazy_static!{
-                static ref REGEX: Regex = Regex::new(
-                    r##"([[:word:]]+)"##
-                ).unwrap();
-            }
-
-            let mut attr_str = String::new();
-
-            stdin().read_line(&mut attr_str).unwrap();
-
-            let mut attri_vec = Vec::new();
-            let mut cnt = 0;
-            for cap in REGEX.captures_iter(attr_str.as_str()) {
-                let attribute =  Attribute::new(cnt, cap[0].to_string());
-                cnt += 1;
-                attri_vec.push(attribute);
-            }
+        Action::Attributes(source) => {
+            let names: Vec<String> = match source {
+                AttributesSource::Names(names) => names,
+                AttributesSource::Csv(path) => {
+                    let header = csv::Reader::from_path(&path).and_then(|mut reader| reader.headers().cloned())
+                        .map_err(|e| format!("Can not read the header of {}: {}", path, e))?;
+                    header.iter().map(|name| name.to_string()).collect()
+                }
+                AttributesSource::Stdin => {
+                    let mut attr_str = String::new();
+                    stdin().read_line(&mut attr_str).map_err(|e| format!("Can not read the standard input: {}", e))?;
+                    parse_names(&attr_str).map_err(|e| e.report(&attr_str))?
+                }
+            };
 
-            attribute_sink.0 = Attributes(attri_vec);
-        }
+            let attri_vec = names.into_iter().enumerate().map(|(rank, name)| Attribute::new(rank, name)).collect();
 
-        _ => {
-            println!("Nothing to do");
+            AttributesSink(Attributes(attri_vec)).save(workspace)?;
         }
     }
-}
 
-fn save_mined_fd(fd: FD)  {
-    let mut fds = FDs::mined();
-    println!("Create FDs: {}", fds.1);
-    fds.0.push(fd);
+    Ok(report)
 }
 
-fn save_new_fd(fd: FD) {
-    let mut fds = FDs::new_fd();
-    fds.0.push(fd);
+// the fds of a collection in the notation of the fd grammar, so they can be checked on any table with the same attribute names
+pub fn collection_fds(workspace: &Workspace, fd_type: Type) -> Result<Vec<String>, String> {
+    Ok(Collection::load(workspace, fd_type.collection())?.0.iter().map(|fd| fd.to_string()).collect())
 }
 
-fn analysis_new_and_output(n: usize, r: f64) {
-    let new_fd = FDs::new_fd();
-    let mined = FDs::mined();
-    
-    new_fd.0.iter().for_each(|fd| {
-        let N = fd.r_neighborhood_cnt(n, r);
-        let NM = mined.0.iter().filter(|fd2| {
-            log::info!("len of fd:{} to {} is :{}", fd, fd2, FD::distance_std(fd, fd2, n));
-            FD::distance_std(fd, fd2, n) < r            
-        }).collect::<Vec<_>>().len();
-
-        println!("fd: {} has N: {}, MN: {}, error: %{}", fd, N, NM, (NM as f64) / (N as f64) * 100.0);
-    });
+// the mined fds as a reasoning set, one fd per dependant attribute
+fn mined_fd_set(workspace: &Workspace, attribute_sink: &AttributesSink) -> Result<crate::dependency::fd::FDs, String> {
+    let mut fds = crate::dependency::fd::FDs::with_attributes("mined".to_string(), Rc::new(attribute_sink.0.0.clone()));
+    for fd in Collection::load(workspace, Type::Mined.collection())?.0.iter() {
+        for dependant in fd.dependant.0.iter() {
+            let mut attr_vec = fd.determinant.0.clone();
+            attr_vec.push(dependant.clone());
+            fds.add(FunctionalDependency::new_from_vec(attr_vec));
+        }
+    }
+    Ok(fds)
 }
 
-pub fn analysis_algorithm_one_and_output(fd: &crate::dependency::fd::FunctionalDependency, n: usize) {
-    let (determinant, dependant) = fd.disintegrate();
-    let new_fd = FD::new_from_vec(determinant, dependant);
-    analysis_one_and_output(&new_fd, n, 4.2);
+// the rows are the report, or they go into the mdfile and the report says where
+fn output_rows(workspace: &Workspace, rows: Vec<AnalysisRow>, output: &Output) -> Result<Vec<String>, String> {
+    if let Output::Mdfile = output {
+        let rows = rows.into_iter().map(|row| {
+            vec![row.fd, row.n.to_string(), row.mn.to_string(), format!("{}%", row.error)]
+        }).collect();
+        Ok(vec![save_markdown(workspace, "Analysis of the fds", &["FD", "N", "MN", "Error"], rows)?])
+    } else {
+        Ok(rows.iter().map(|row| row.to_string()).collect())
+    }
 }
 
-fn analysis_one_and_output(fd: &FD, n: usize, r: f64) {
-    let mined = FDs::mined();
+// the r-neighbourhood of every mined fd against the mined collection of the workspace, in the order of fds.
+// without a workspace nothing was mined before
+pub fn analysis_rows(workspace: Option<&Workspace>, fds: &crate::dependency::fd::FDs) -> Result<Vec<AnalysisRow>, String> {
+    let mined = match workspace {
+        Some(workspace) => Collection::load(workspace, Type::Mined.collection())?,
+        None => Collection::default()
+    };
+    let n = fds.attributes().len();
+
+    Ok(fds.iter().map(|fd| {
+        let (determinant, dependant) = fd.disintegrate();
+        analysis_row(&FD::new_from_vec(determinant, dependant), &mined, n, 4.2)
+    }).collect())
+}
 
+fn analysis_row(fd: &FD, mined: &Collection, n: usize, r: f64) -> AnalysisRow {
     let N = fd.r_neighborhood_cnt(n, r);
     let NM = mined.0.iter().filter(|fd2| {
         log::info!("len of fd:{} to {} is :{}", fd, fd2, FD::distance_std(fd, fd2, n));
-        FD::distance_std(fd, fd2, n) < r            
+        FD::distance_std(fd, fd2, n) < r
     }).collect::<Vec<_>>().len();
-    
-    println!("FD: {} has N: {}, MN: {}, error: %{}", fd,  N, NM, (NM as f64) / (N as f64) * 100.0);
-}
\ No newline at end of file
+
+    AnalysisRow { fd: fd.to_string(), n: N, mn: NM, error: (NM as f64) / (N as f64) * 100.0 }
+}
+
+// the markdown report goes next to the collections, the line tells where
+fn save_markdown(workspace: &Workspace, title: &str, header: &[&str], rows: Vec<Vec<String>>) -> Result<String, String> {
+    let path = workspace.write_report(ANALYSIS_MDFILE, |tmp| write_markdown(&tmp.to_string_lossy(), title, header, rows))
+        .map_err(|e| format!("Can not write {}: {}", workspace.dir().join(ANALYSIS_MDFILE).display(), e))?;
+    Ok(format!("The report is written to {}", path.display()))
+}
@@ -0,0 +1,210 @@
+use crate::frontend::table::{Attribute, Table};
+use crate::frontend::partition::NullModel;
+use crate::dependency::result::{AlgorithmResult, Format, Statistics, write_csv, write_json, write_markdown};
+use crate::dependency::fd::Attributes;
+use crate::dependency::syntax::quote_name;
+use std::fmt::{Display, Formatter};
+use std::rc::Rc;
+use serde::{Serialize, Serializer};
+use serde::ser::SerializeStruct;
+
+// an entry of a pattern tuple, `_` matches every value
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum PatternValue {
+    Constant(String),
+    Wildcard
+}
+
+impl PatternValue {
+    pub fn is_constant(&self) -> bool {
+        matches!(self, PatternValue::Constant(_))
+    }
+}
+
+impl Display for PatternValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PatternValue::Constant(value) => write!(f, "'{}'", value.replace('\'', "''")),
+            PatternValue::Wildcard => write!(f, "_")
+        }
+    }
+}
+
+impl Serialize for PatternValue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            PatternValue::Constant(value) => serializer.serialize_some(value),
+            PatternValue::Wildcard => serializer.serialize_none()
+        }
+    }
+}
+
+// one row of a tableau, the support is the number of rows matching the lhs
+// and the confidence the share of them the cfd holds on
+#[derive(Clone, Debug, Serialize)]
+pub struct Pattern {
+    pub lhs: Vec<PatternValue>,
+    pub rhs: PatternValue,
+    pub support: usize,
+    pub confidence: f64
+}
+
+impl Pattern {
+    // constant cfds only have constants, the ones a rules engine checks row by row
+    pub fn is_constant(&self) -> bool {
+        self.rhs.is_constant() && self.lhs.iter().all(|v| v.is_constant())
+    }
+}
+
+// the patterns of one embedded fd X -> A
+#[derive(Clone, Serialize)]
+pub struct PatternTableau {
+    pub determinant: Attributes,
+    pub dependant: Attribute,
+    pub patterns: Vec<Pattern>
+}
+
+impl PatternTableau {
+    // the pattern as a single cfd, like [country='UK', zip] -> city
+    pub fn rule(&self, pattern: &Pattern) -> String {
+        let side = |a: &Attribute, value: &PatternValue| match value {
+            PatternValue::Constant(_) => format!("{}={}", quote_name(&a.value), value),
+            PatternValue::Wildcard => quote_name(&a.value).to_string()
+        };
+        let lhs: String = self.determinant.0.iter().zip(pattern.lhs.iter())
+            .map(|(a, value)| side(a, value))
+            .intersperse(", ".to_string())
+            .collect();
+        format!("[{}] -> {}", lhs, side(&self.dependant, &pattern.rhs))
+    }
+}
+
+impl Display for PatternTableau {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} -> {}", self.determinant, quote_name(&self.dependant.value))?;
+        for pattern in self.patterns.iter() {
+            write!(f, "\n    {}  support {}, confidence {:.4}", self.rule(pattern), pattern.support, pattern.confidence)?;
+        }
+        Ok(())
+    }
+}
+
+pub struct CFDs {
+    tableaux: Vec<PatternTableau>,
+    table_name: String,
+    attributes: Rc<Vec<Attribute>>,
+    null_model: NullModel,
+    statistics: Statistics
+}
+
+impl CFDs {
+    pub fn new(table: &Table) -> CFDs {
+        CFDs { tableaux: Vec::new(), table_name: table.table_name.clone(), attributes: Rc::clone(&table.attributes), null_model: NullModel::Equal, statistics: Statistics::default() }
+    }
+
+    pub fn with_null_model(mut self, null_model: NullModel) -> CFDs {
+        self.null_model = null_model;
+        self
+    }
+
+    pub fn null_model(&self) -> NullModel {
+        self.null_model
+    }
+
+    pub fn table_name(&self) -> &str {
+        &self.table_name
+    }
+
+    pub fn attributes(&self) -> &Rc<Vec<Attribute>> {
+        &self.attributes
+    }
+
+    pub fn set_statistics(&mut self, statistics: Statistics) {
+        self.statistics = statistics;
+    }
+
+    pub fn len(&self) -> usize {
+        self.tableaux.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tableaux.is_empty()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, PatternTableau> {
+        self.tableaux.iter()
+    }
+
+    pub fn add(&mut self, tableau: PatternTableau) {
+        self.tableaux.push(tableau);
+    }
+
+    // every pattern with its tableau
+    pub fn patterns(&self) -> impl Iterator<Item = (&PatternTableau, &Pattern)> {
+        self.tableaux.iter().flat_map(|tableau| tableau.patterns.iter().map(move |pattern| (tableau, pattern)))
+    }
+}
+
+impl Display for CFDs {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "We have conditional functional dependencies of the table {} ({}):", self.table_name, self.null_model)?;
+        for tableau in self.tableaux.iter() {
+            writeln!(f, "CFD: {}", tableau)?;
+        }
+        Ok(())
+    }
+}
+
+impl Serialize for CFDs {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("CFDs", 5)?;
+        state.serialize_field("table_name", &self.table_name)?;
+        state.serialize_field("attributes", self.attributes.as_ref())?;
+        state.serialize_field("null_model", &self.null_model)?;
+        state.serialize_field("statistics", &self.statistics)?;
+        state.serialize_field("tableaux", &self.tableaux)?;
+        state.end()
+    }
+}
+
+impl AlgorithmResult for CFDs {
+    type Dependency = PatternTableau;
+
+    fn dependencies(&self) -> &[PatternTableau] {
+        &self.tableaux
+    }
+
+    fn statistics(&self) -> &Statistics {
+        &self.statistics
+    }
+
+    fn save_as_file(&self, path: &str, format: Format) -> Result<(), std::io::Error> {
+        match format {
+            Format::Json => write_json(path, self),
+            Format::Csv => {
+                let rows = self.patterns().map(|(tableau, pattern)| vec![
+                    self.table_name.clone(),
+                    tableau.determinant.0.iter().map(|a| a.value.clone()).intersperse(";".to_string()).collect(),
+                    pattern.lhs.iter().map(|v| v.to_string()).intersperse(";".to_string()).collect(),
+                    tableau.dependant.value.clone(),
+                    pattern.rhs.to_string(),
+                    pattern.support.to_string(),
+                    pattern.confidence.to_string(),
+                    if pattern.is_constant() { "constant".to_string() } else { "variable".to_string() },
+                    self.null_model.to_string()
+                ]).collect();
+
+                write_csv(path, &["table", "determinant", "lhs_pattern", "dependant", "rhs_pattern", "support", "confidence", "kind", "null_model"], rows)
+            }
+            Format::Markdown => {
+                let rows = self.patterns().map(|(tableau, pattern)| vec![
+                    tableau.rule(pattern),
+                    pattern.support.to_string(),
+                    format!("{:.4}", pattern.confidence)
+                ]).collect();
+
+                write_markdown(path, &format!("Conditional functional dependencies of {} ({})", self.table_name, self.null_model), &["CFD", "Support", "Confidence"], rows)
+            }
+        }
+    }
+}
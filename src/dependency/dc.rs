@@ -0,0 +1,226 @@
+use crate::frontend::table::{Attribute, Table};
+use crate::frontend::partition::NullModel;
+use crate::frontend::types::ColumnType;
+use crate::dependency::result::{AlgorithmResult, Format, Statistics, write_csv, write_json, write_markdown};
+use crate::dependency::syntax::quote_name;
+use std::fmt::{Display, Formatter};
+use std::rc::Rc;
+use serde::{Serialize, Serializer};
+use serde::ser::SerializeStruct;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+pub enum Operator {
+    #[serde(rename = "=")]
+    Equal,
+    #[serde(rename = "!=")]
+    NotEqual,
+    #[serde(rename = "<")]
+    Less,
+    #[serde(rename = ">")]
+    Greater,
+    #[serde(rename = "<=")]
+    LessEqual,
+    #[serde(rename = ">=")]
+    GreaterEqual
+}
+
+impl Operator {
+    pub const ALL: [Operator; 6] = [Operator::Equal, Operator::NotEqual, Operator::Less, Operator::Greater, Operator::LessEqual, Operator::GreaterEqual];
+
+    // the operator with its operands swapped, t.A < s.B is s.B > t.A
+    pub fn converse(&self) -> Operator {
+        match self {
+            Operator::Less => Operator::Greater,
+            Operator::Greater => Operator::Less,
+            Operator::LessEqual => Operator::GreaterEqual,
+            Operator::GreaterEqual => Operator::LessEqual,
+            op => *op
+        }
+    }
+
+    // every pair of values satisfying self satisfies other, like < and ≤
+    pub fn implies(&self, other: Operator) -> bool {
+        // the relations <, = and > an operator is satisfied by
+        let relations = |op: Operator| match op {
+            Operator::Less => 0b001,
+            Operator::Equal => 0b010,
+            Operator::Greater => 0b100,
+            Operator::NotEqual => 0b101,
+            Operator::LessEqual => 0b011,
+            Operator::GreaterEqual => 0b110
+        };
+        relations(*self) & !relations(other) == 0
+    }
+
+    // only = and ≠ apply to strings
+    pub fn is_order(&self) -> bool {
+        !matches!(self, Operator::Equal | Operator::NotEqual)
+    }
+}
+
+impl Display for Operator {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Operator::Equal => write!(f, "="),
+            Operator::NotEqual => write!(f, "≠"),
+            Operator::Less => write!(f, "<"),
+            Operator::Greater => write!(f, ">"),
+            Operator::LessEqual => write!(f, "≤"),
+            Operator::GreaterEqual => write!(f, "≥")
+        }
+    }
+}
+
+// t.A op s.B over a pair of rows, or t.A op t.B over one row when same_tuple is set
+#[derive(Clone, Debug, Serialize)]
+pub struct Predicate {
+    pub left: Attribute,
+    pub operator: Operator,
+    pub right: Attribute,
+    pub same_tuple: bool
+}
+
+impl Display for Predicate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let tuple = if self.same_tuple { "t" } else { "s" };
+        write!(f, "t.{} {} {}.{}", quote_name(&self.left.value), self.operator, tuple, quote_name(&self.right.value))
+    }
+}
+
+// no two rows satisfy all the predicates at once. the violations are the ordered row pairs that do,
+// only approximate dcs have some
+#[derive(Clone, Debug, Serialize)]
+pub struct DenialConstraint {
+    pub predicates: Vec<Predicate>,
+    pub violations: usize,
+    pub error: f64
+}
+
+impl Display for DenialConstraint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let predicates: String = self.predicates.iter().map(|p| p.to_string()).intersperse(" ∧ ".to_string()).collect();
+        write!(f, "¬({})", predicates)
+    }
+}
+
+pub struct DCs {
+    dcs: Vec<DenialConstraint>,
+    table_name: String,
+    attributes: Rc<Vec<Attribute>>,
+    column_types: Vec<ColumnType>,
+    null_model: NullModel,
+    statistics: Statistics
+}
+
+impl DCs {
+    pub fn new(table: &Table, column_types: Vec<ColumnType>) -> DCs {
+        DCs { dcs: Vec::new(), table_name: table.table_name.clone(), attributes: Rc::clone(&table.attributes), column_types, null_model: NullModel::Equal, statistics: Statistics::default() }
+    }
+
+    pub fn with_null_model(mut self, null_model: NullModel) -> DCs {
+        self.null_model = null_model;
+        self
+    }
+
+    pub fn null_model(&self) -> NullModel {
+        self.null_model
+    }
+
+    pub fn table_name(&self) -> &str {
+        &self.table_name
+    }
+
+    pub fn attributes(&self) -> &Rc<Vec<Attribute>> {
+        &self.attributes
+    }
+
+    pub fn column_types(&self) -> &[ColumnType] {
+        &self.column_types
+    }
+
+    pub fn set_statistics(&mut self, statistics: Statistics) {
+        self.statistics = statistics;
+    }
+
+    pub fn len(&self) -> usize {
+        self.dcs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.dcs.is_empty()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, DenialConstraint> {
+        self.dcs.iter()
+    }
+
+    pub fn add(&mut self, dc: DenialConstraint) {
+        self.dcs.push(dc);
+    }
+}
+
+impl Display for DCs {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "We have denial constraints of the table {} ({}):", self.table_name, self.null_model)?;
+        for dc in self.dcs.iter() {
+            if dc.violations > 0 {
+                writeln!(f, "DC: {} (violations: {}, error: {:.4})", dc, dc.violations, dc.error)?;
+            } else {
+                writeln!(f, "DC: {}", dc)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Serialize for DCs {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("DCs", 6)?;
+        state.serialize_field("table_name", &self.table_name)?;
+        state.serialize_field("attributes", self.attributes.as_ref())?;
+        state.serialize_field("column_types", &self.column_types)?;
+        state.serialize_field("null_model", &self.null_model)?;
+        state.serialize_field("statistics", &self.statistics)?;
+        state.serialize_field("dcs", &self.dcs)?;
+        state.end()
+    }
+}
+
+impl AlgorithmResult for DCs {
+    type Dependency = DenialConstraint;
+
+    fn dependencies(&self) -> &[DenialConstraint] {
+        &self.dcs
+    }
+
+    fn statistics(&self) -> &Statistics {
+        &self.statistics
+    }
+
+    fn save_as_file(&self, path: &str, format: Format) -> Result<(), std::io::Error> {
+        match format {
+            Format::Json => write_json(path, self),
+            Format::Csv => {
+                let rows = self.dcs.iter().map(|dc| vec![
+                    self.table_name.clone(),
+                    dc.predicates.iter().map(|p| p.to_string()).intersperse(";".to_string()).collect(),
+                    dc.predicates.len().to_string(),
+                    dc.violations.to_string(),
+                    dc.error.to_string(),
+                    self.null_model.to_string()
+                ]).collect();
+
+                write_csv(path, &["table", "predicates", "size", "violations", "error", "null_model"], rows)
+            }
+            Format::Markdown => {
+                let rows = self.dcs.iter().map(|dc| vec![
+                    dc.to_string(),
+                    dc.violations.to_string(),
+                    format!("{:.4}", dc.error)
+                ]).collect();
+
+                write_markdown(path, &format!("Denial constraints of {} ({})", self.table_name, self.null_model), &["DC", "Violations", "Error"], rows)
+            }
+        }
+    }
+}
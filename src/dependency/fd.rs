@@ -1,25 +1,51 @@
 use crate::frontend::table::{Attribute, Table};
-use crate::dependency::result::AlgorithmResult;
-use crate::dependency::analysis::analysis_algorithm_one_and_output;
+use crate::frontend::partition::NullModel;
+use crate::dependency::syntax::{parse_fd, quote_name, ParseError};
+use crate::dependency::result::{AlgorithmResult, Format, Statistics, write_csv, write_json, write_markdown};
 use bit_set::BitSet;
 use std::fmt::{Display, Formatter};
+use std::collections::HashMap;
 use std::rc::Rc;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Serialize, Serializer};
+use serde::ser::SerializeStruct;
 
-#[derive(Deserialize, Serialize)]
+#[derive(Clone, Deserialize, Serialize)]
 pub struct Attributes(pub Vec<Attribute>);
 
-#[derive(Deserialize, Serialize)]
+#[derive(Clone, Deserialize, Serialize)]
 pub struct FunctionalDependency {
     determinant: Attributes,
-    dependant: Attribute
+    dependant: Attribute,
+    #[serde(default)]
+    error: f64
 }
 
 impl FunctionalDependency {
+    // g3 error measured on the mined table, 0 for exact fds
+    pub fn error(&self) -> f64 {
+        self.error
+    }
+
+    pub fn determinant_set(&self) -> BitSet {
+        self.determinant.0.iter().map(|a| a.rank).collect()
+    }
+
+    pub fn dependant_rank(&self) -> usize {
+        self.dependant.rank
+    }
+
     pub fn disintegrate(&self) -> (Vec<Attribute>, Vec<Attribute>) {
         (self.determinant.0.clone(), vec![self.dependant.clone()])
     }
 
+    // one fd per dependant attribute of the input, see dependency::syntax for the grammar
+    pub fn parse(input: &str, attributes: &[Attribute]) -> Result<Vec<FunctionalDependency>, ParseError> {
+        let (determinant, dependant) = parse_fd(input)?.resolve(attributes)?;
+        Ok(dependant.into_iter().map(|dependant| {
+            FunctionalDependency { determinant: Attributes(determinant.clone()), dependant, error: 0.0 }
+        }).collect())
+    }
+
     pub fn new_from_vec(attr_vec: Vec<Attribute>) -> FunctionalDependency {
         let mut dependant = Attribute {value: "".to_string(), rank: 0};
         
@@ -39,7 +65,7 @@ impl FunctionalDependency {
 
         let determinant = Attributes(group);
 
-        FunctionalDependency { determinant, dependant }
+        FunctionalDependency { determinant, dependant, error: 0.0 }
 
     }
 
@@ -68,68 +94,100 @@ impl FunctionalDependency {
         Self::distance(fd1, fd2, 1.0, (n - 1.0) / (n * n), 1.0 / (n * n))
     }
 
-    pub fn r_neighborhood_cnt(&self, n: usize, r: f64) -> usize {
-        fn dfs(index: usize, n: usize, deter_set: &mut BitSet, fd: &FunctionalDependency, r: f64) -> usize {
-            let mut cnt = 0;
-            if index < n {
-                cnt += dfs(index + 1, n, deter_set, fd, r);
-                deter_set.insert(index);
-                cnt += dfs(index + 1, n, deter_set, fd, r);
-                deter_set.remove(index);
-            } else if deter_set.len() != n {
-                let mut least: BitSet = (0..n).collect();
-                least.difference_with(deter_set);
-                for idx in least.iter() {
-                    let distance = |x1: &BitSet, y1: &BitSet, x2: &BitSet, y2: &BitSet| {
-                        let s1: BitSet = x1.union(&y1).collect();
-                        let s2: BitSet = x2.union(&y2).collect();
-
-                        let cnt1 = s1.symmetric_difference(&s2).collect::<BitSet>().len() as f64;
-                        let cnt2 = x1.symmetric_difference(&x2).collect::<BitSet>().len() as f64;
-                        let cnt3 = y1.symmetric_difference(&y2).collect::<BitSet>().len() as f64;
-
-                        let n = n as f64;
-
-                        let delta1 = 1.0;
-                        let delta2 = (n - 1.0) / (n * n);
-                        let delta3 = 1.0 / (n * n);
-
-                        delta1 * cnt1 + delta2 * cnt2 + delta3 * cnt3
-                    };
-
-                    let x1: BitSet = fd.determinant.0.iter().map(|a| a.rank).collect();
-                    let mut y1 = BitSet::new();
-                    y1.insert(fd.dependant.rank);
-
-                    let mut y2 = BitSet::new();
-                    y2.insert(idx);
-
-                    if distance(&x1, &y1, &deter_set, &y2) < r {
-                        cnt += 1;
+    pub fn r_neighborhood_cnt(&self, n: usize, r: f64) -> u128 {
+        let x1: BitSet = self.determinant.0.iter().map(|a| a.rank).collect();
+        let mut y1 = BitSet::new();
+        y1.insert(self.dependant.rank);
+
+        r_neighborhood_size(&x1, &y1, n, r)
+    }
+}
+
+
+// the number of fds X2 -> a over n attributes, a not in X2, whose standard distance to X1 -> Y1 is below r.
+// the distance only counts symmetric differences, so the attributes are grouped by their membership in X1 and Y1
+// and the fds of every class are counted at once by a dp over the three difference counts.
+// the counts only grow, so a state already at distance r is dropped. past u128::MAX the count saturates
+pub fn r_neighborhood_size(x1: &BitSet, y1: &BitSet, n: usize, r: f64) -> u128 {
+    let mut classes = [0usize; 4];
+    for u in 0..n {
+        classes[(x1.contains(u) as usize) << 1 | y1.contains(u) as usize] += 1;
+    }
+
+    let nf = n as f64;
+    let (delta1, delta2, delta3) = (1.0, (nf - 1.0) / (nf * nf), 1.0 / (nf * nf));
+    let within = |c1: usize, c2: usize, c3: usize| delta1 * (c1 as f64) + delta2 * (c2 as f64) + delta3 * (c3 as f64) < r;
+
+    // (|S1 Δ S2|, |X1 Δ X2|, |Y1 Δ {a}|, a chosen) -> number of fds
+    let mut states: HashMap<(usize, usize, usize, bool), u128> = HashMap::new();
+    states.insert((0, 0, 0, false), 1);
+
+    for (class, size) in classes.into_iter().enumerate() {
+        let (x, y) = (class >> 1 == 1, class & 1 == 1);
+        // what one attribute of the class adds to the counts as a member of X2, as a or when left out
+        let in_lhs = [!(x || y) as usize, !x as usize, y as usize];
+        let in_rhs = [!(x || y) as usize, x as usize, !y as usize];
+        let unused = [(x || y) as usize, x as usize, y as usize];
+
+        let mut next = HashMap::new();
+        for ((c1, c2, c3, chosen), cnt) in states {
+            let rhs_choices = if chosen || size == 0 { 0 } else { 1 };
+            for rhs in 0..=rhs_choices {
+                let rest = size - rhs;
+                let ways = if rhs == 1 { cnt.saturating_mul(size as u128) } else { cnt };
+                for k in 0..=rest {
+                    let add = |i: usize| k * in_lhs[i] + (rest - k) * unused[i] + rhs * in_rhs[i];
+                    let key = (c1 + add(0), c2 + add(1), c3 + add(2), chosen || rhs == 1);
+                    if !within(key.0, key.1, key.2) {
+                        continue;
                     }
+                    let entry = next.entry(key).or_insert(0u128);
+                    *entry = entry.saturating_add(ways.saturating_mul(binomial(rest, k)));
                 }
             }
-    
-            cnt
         }
-        
-        let mut deter_set = BitSet::new();
-
-        dfs(0, n, &mut deter_set, self, r)
+        states = next;
     }
+
+    states.into_iter()
+        .filter(|((c1, c2, c3, chosen), _)| *chosen && within(*c1, *c2, *c3))
+        .fold(0u128, |sum, (_, cnt)| sum.saturating_add(cnt))
 }
 
+// C(n, k), u128::MAX when it does not fit. the gcd keeps the partial products exact and small
+fn binomial(n: usize, k: usize) -> u128 {
+    let gcd = |mut a: u128, mut b: u128| {
+        while b != 0 {
+            (a, b) = (b, a % b);
+        }
+        a
+    };
+
+    let k = k.min(n - k);
+    let mut res = 1u128;
+    for i in 0..k {
+        // res * (n - i) is divisible by i + 1, and what is left of i + 1 after the gcd divides n - i
+        let g = gcd(res, (i + 1) as u128);
+        let factor = (n - i) as u128 / ((i + 1) as u128 / g);
+        match (res / g).checked_mul(factor) {
+            Some(next) => res = next,
+            None => return u128::MAX
+        }
+    }
+    res
+}
 
 impl Display for Attributes {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        let s: String = self.0.iter().map(|a| a.value.clone()).intersperse(", ".to_string()).collect();
+        // names are quoted where needed, so the fds can be parsed back
+        let s: String = self.0.iter().map(|a| quote_name(&a.value).to_string()).intersperse(", ".to_string()).collect();
         write!(f, "{{{}}}", s)
     }
 }
 
 impl Display for FunctionalDependency {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{} -> {}", self.determinant, self.dependant)
+        write!(f, "{} -> {}", self.determinant, quote_name(&self.dependant.value))
     }
 }
 
@@ -137,18 +195,58 @@ pub struct FDs {
     fds: Vec<FunctionalDependency>,
     table_name: String,
     attributes: Rc<Vec<Attribute>>,
+    null_model: NullModel,
+    statistics: Statistics
 }
 
 impl FDs {
     pub fn new(table: &Table) -> FDs {
-        FDs {fds: Vec::new(), table_name: table.table_name.clone(), attributes: Rc::clone(&table.attributes)}
+        FDs::with_attributes(table.table_name.clone(), Rc::clone(&table.attributes))
+    }
+
+    pub fn with_attributes(table_name: String, attributes: Rc<Vec<Attribute>>) -> FDs {
+        FDs {fds: Vec::new(), table_name, attributes, null_model: NullModel::Equal, statistics: Statistics::default()}
+    }
+
+    // the null model the fds were mined under
+    pub fn with_null_model(mut self, null_model: NullModel) -> FDs {
+        self.null_model = null_model;
+        self
+    }
+
+    pub fn null_model(&self) -> NullModel {
+        self.null_model
+    }
+
+    pub fn table_name(&self) -> &str {
+        &self.table_name
+    }
+
+    pub fn attributes(&self) -> &Rc<Vec<Attribute>> {
+        &self.attributes
+    }
+
+    pub fn set_statistics(&mut self, statistics: Statistics) {
+        self.statistics = statistics;
+    }
+
+    pub fn len(&self) -> usize {
+        self.fds.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fds.is_empty()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, FunctionalDependency> {
+        self.fds.iter()
     }
 
     pub fn add(&mut self, fd: FunctionalDependency) {
         self.fds.push(fd);
     }
 
-    pub fn add_from_index(&mut self, X: &BitSet, a: usize) {
+    pub fn add_from_index(&mut self, X: &BitSet, a: usize, error: f64) {
         let mut determinant: Vec<_> = X.iter().map(|index| {
             self.attributes.get(index).unwrap().clone()
         }).collect();
@@ -159,13 +257,68 @@ impl FDs {
 
         let dependant = self.attributes.get(a).unwrap().clone();
 
-        let fd = FunctionalDependency {determinant, dependant};
-        // println!("FD: {}", fd);
+        let fd = FunctionalDependency {determinant, dependant, error};
 
         self.add(fd);
     }
 
-    pub fn r_neighborhood(&self, fd: &FunctionalDependency, r: f64) -> usize {
+    // X+, every attribute determined by X under the fd set
+    pub fn closure(&self, X: &BitSet) -> BitSet {
+        closure_of(&self.index_pairs(), X)
+    }
+
+    pub fn implies(&self, fd: &FunctionalDependency) -> bool {
+        self.closure(&fd.determinant_set()).contains(fd.dependant.rank)
+    }
+
+    // drops every fd implied by the remaining ones, in order
+    pub fn remove_redundant(&mut self) {
+        let mut pairs = self.index_pairs();
+        let mut index = 0;
+        while index < pairs.len() {
+            let (lhs, rhs) = pairs.remove(index);
+            if closure_of(&pairs, &lhs).contains(rhs) {
+                self.fds.remove(index);
+            } else {
+                pairs.insert(index, (lhs, rhs));
+                index += 1;
+            }
+        }
+    }
+
+    // left reduced, duplicate free and non redundant fds equivalent to this set
+    pub fn minimal_cover(&self) -> FDs {
+        let pairs = self.index_pairs();
+        let mut cover = FDs::with_attributes(self.table_name.clone(), Rc::clone(&self.attributes)).with_null_model(self.null_model);
+
+        for (fd, (lhs, rhs)) in self.fds.iter().zip(pairs.iter()) {
+            if lhs.contains(*rhs) {
+                continue;
+            }
+
+            let mut reduced = lhs.clone();
+            for b in lhs.iter() {
+                reduced.remove(b);
+                if !closure_of(&pairs, &reduced).contains(*rhs) {
+                    reduced.insert(b);
+                }
+            }
+
+            let duplicated = cover.fds.iter().any(|f| f.dependant.rank == *rhs && f.determinant_set() == reduced);
+            if !duplicated {
+                cover.add_from_index(&reduced, *rhs, fd.error);
+            }
+        }
+
+        cover.remove_redundant();
+        cover
+    }
+
+    fn index_pairs(&self) -> Vec<(BitSet, usize)> {
+        self.fds.iter().map(|fd| (fd.determinant_set(), fd.dependant.rank)).collect()
+    }
+
+    pub fn r_neighborhood(&self, fd: &FunctionalDependency, r: f64) -> u128 {
         let n = self.attributes.len();
         fd.r_neighborhood_cnt(n, r)
     }
@@ -184,19 +337,223 @@ impl FDs {
     }
 }
 
+fn closure_of(fds: &[(BitSet, usize)], X: &BitSet) -> BitSet {
+    let mut closure = X.clone();
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for (lhs, rhs) in fds.iter() {
+            if !closure.contains(*rhs) && lhs.is_subset(&closure) {
+                closure.insert(*rhs);
+                changed = true;
+            }
+        }
+    }
+    closure
+}
+
+impl Display for FDs {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "We have functional dependency set of the table {} ({}):", self.table_name, self.null_model)?;
+        for fd in self.fds.iter() {
+            if fd.error > 0.0 {
+                writeln!(f, "FD: {} (g3 error: {:.4})", fd, fd.error)?;
+            } else {
+                writeln!(f, "FD: {}", fd)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Serialize for FDs {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("FDs", 5)?;
+        state.serialize_field("table_name", &self.table_name)?;
+        state.serialize_field("attributes", self.attributes.as_ref())?;
+        state.serialize_field("null_model", &self.null_model)?;
+        state.serialize_field("statistics", &self.statistics)?;
+        state.serialize_field("fds", &self.fds)?;
+        state.end()
+    }
+}
+
 impl AlgorithmResult for FDs {
-    fn display(&self) {
-        println!("We have functional dependency set of the table {}:", self.table_name);
+    type Dependency = FunctionalDependency;
+
+    fn dependencies(&self) -> &[FunctionalDependency] {
+        &self.fds
+    }
+
+    fn statistics(&self) -> &Statistics {
+        &self.statistics
+    }
+
+    fn save_as_file(&self, path: &str, format: Format) -> Result<(), std::io::Error> {
+        match format {
+            Format::Json => write_json(path, self),
+            Format::Csv => {
+                let rows = self.fds.iter().map(|fd| vec![
+                    self.table_name.clone(),
+                    fd.determinant.0.iter().map(|a| a.value.clone()).intersperse(";".to_string()).collect(),
+                    fd.determinant.0.iter().map(|a| a.rank.to_string()).intersperse(";".to_string()).collect(),
+                    fd.dependant.value.clone(),
+                    fd.dependant.rank.to_string(),
+                    fd.error.to_string(),
+                    self.null_model.to_string()
+                ]).collect();
+
+                write_csv(path, &["table", "determinant", "determinant_ranks", "dependant", "dependant_rank", "error", "null_model"], rows)
+            }
+            Format::Markdown => {
+                let rows = self.fds.iter().map(|fd| vec![
+                    fd.determinant.0.iter().map(|a| format!("{} ({})", a.value, a.rank)).intersperse(", ".to_string()).collect(),
+                    format!("{} ({})", fd.dependant.value, fd.dependant.rank),
+                    format!("{:.4}", fd.error)
+                ]).collect();
+
+                write_markdown(path, &format!("Functional dependencies of {} ({})", self.table_name, self.null_model), &["Determinant", "Dependant", "g3 error"], rows)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // every fd X2 -> a with a not in X2, as the analysis enumerated it before
+    fn brute_force(x1: &BitSet, y1: &BitSet, n: usize, r: f64) -> u128 {
+        let distance = |x2: &BitSet, y2: &BitSet| {
+            let s1: BitSet = x1.union(y1).collect();
+            let s2: BitSet = x2.union(y2).collect();
+            let cnt1 = s1.symmetric_difference(&s2).count() as f64;
+            let cnt2 = x1.symmetric_difference(x2).count() as f64;
+            let cnt3 = y1.symmetric_difference(y2).count() as f64;
+            let n = n as f64;
+            1.0 * cnt1 + (n - 1.0) / (n * n) * cnt2 + 1.0 / (n * n) * cnt3
+        };
+
         let mut cnt = 0;
-        for fd in self.fds.iter() {
-            // println!("FD{}: {};",cnt, fd);
-            analysis_algorithm_one_and_output(fd, self.attributes.len());
-            cnt += 1;
+        for mask in 0..(1usize << n) {
+            let x2: BitSet = (0..n).filter(|i| mask & (1 << i) != 0).collect();
+            for a in (0..n).filter(|a| !x2.contains(*a)) {
+                let y2: BitSet = [a].into_iter().collect();
+                if distance(&x2, &y2) < r {
+                    cnt += 1;
+                }
+            }
         }
+        cnt
     }
 
-    fn save_as_file(&self) -> Result<(), std::io::Error> {
+    #[test]
+    fn r_neighborhood_matches_brute_force() {
+        let cases: Vec<(Vec<usize>, Vec<usize>)> = vec![
+            (vec![], vec![0]),
+            (vec![0], vec![1]),
+            (vec![0, 2], vec![1]),
+            (vec![1, 2, 3], vec![0]),
+            (vec![0], vec![1, 2])
+        ];
+
+        for n in 3..=8 {
+            for (x, y) in cases.iter().filter(|(x, y)| x.iter().chain(y.iter()).all(|a| *a < n)) {
+                let x1: BitSet = x.iter().cloned().collect();
+                let y1: BitSet = y.iter().cloned().collect();
+                for r in [0.5, 1.0, 1.5, 2.2, 3.0, 4.2, 10.0] {
+                    assert_eq!(r_neighborhood_size(&x1, &y1, n, r), brute_force(&x1, &y1, n, r), "{:?} -> {:?}, n {}, r {}", x, y, n, r);
+                }
+            }
+        }
+    }
 
-        Ok(())
+    #[test]
+    fn r_neighborhood_is_polynomial() {
+        let x1: BitSet = (0..10).collect();
+        let y1: BitSet = [10].into_iter().collect();
+        // every fd over 100 attributes, n * 2^(n - 1) of them
+        assert_eq!(r_neighborhood_size(&x1, &y1, 100, f64::INFINITY), 100 * (1u128 << 99));
+        assert_eq!(r_neighborhood_size(&x1, &y1, 120, f64::INFINITY), 120 * (1u128 << 119));
+    }
+
+    #[test]
+    fn r_neighborhood_of_wide_tables() {
+        let x1: BitSet = (0..10).collect();
+        let y1: BitSet = [10].into_iter().collect();
+        // below 1 only the fds over the same 11 attributes are left, one per dependant
+        assert_eq!(r_neighborhood_size(&x1, &y1, 200, 1.0), 11);
+        // 200 * 2^199 fds do not fit
+        assert_eq!(r_neighborhood_size(&x1, &y1, 200, f64::INFINITY), u128::MAX);
+    }
+
+    #[test]
+    fn binomials_saturate() {
+        assert_eq!(binomial(10, 3), 120);
+        assert_eq!(binomial(140, 70), u128::MAX);
+        // C(128, 64) is about 2^124.2
+        assert_eq!(binomial(128, 64), 23951146041928082866135587776380551750);
+        assert_eq!(binomial(128, 1), 128);
+    }
+
+    fn fd_set(names: &str, fds: &[&str]) -> FDs {
+        let attributes: Vec<Attribute> = names.chars().enumerate().map(|(rank, name)| Attribute::new(rank, name.to_string())).collect();
+        let mut res = FDs::with_attributes("r".to_string(), Rc::new(attributes));
+        for fd in fds {
+            FunctionalDependency::parse(fd, &res.attributes.clone()).unwrap().into_iter().for_each(|fd| res.add(fd));
+        }
+        res
+    }
+
+    fn attributes(fds: &FDs, names: &str) -> BitSet {
+        names.chars().map(|c| fds.attributes.iter().position(|a| a.value == c.to_string()).unwrap()).collect()
+    }
+
+    fn rendered(fds: &FDs) -> Vec<String> {
+        fds.iter().map(|fd| fd.to_string()).collect()
+    }
+
+    #[test]
+    fn closure_of_the_textbook_example() {
+        // Silberschatz, Korth and Sudarshan: (AG)+ is ABCGHI
+        let fds = fd_set("ABCGHI", &["A -> B", "A -> C", "C, G -> H", "C, G -> I", "B -> H"]);
+        assert_eq!(fds.closure(&attributes(&fds, "AG")), attributes(&fds, "ABCGHI"));
+        assert_eq!(fds.closure(&attributes(&fds, "A")), attributes(&fds, "ABCH"));
+        assert_eq!(fds.closure(&attributes(&fds, "CG")), attributes(&fds, "CGHI"));
+
+        let implied = |fd: &str| FunctionalDependency::parse(fd, &fds.attributes).unwrap().iter().all(|fd| fds.implies(fd));
+        assert!(implied("A -> H"));
+        assert!(implied("C, G -> H, I"));
+        assert!(implied("A, G -> I"));
+        assert!(!implied("G -> H"));
+        assert!(!implied("B -> A"));
+    }
+
+    #[test]
+    fn redundant_fds_are_dropped_in_order() {
+        let mut fds = fd_set("ABC", &["A -> B", "B -> C", "A -> C", "A, B -> C"]);
+        fds.remove_redundant();
+        assert_eq!(rendered(&fds), vec!["{A} -> B", "{B} -> C"]);
+    }
+
+    #[test]
+    fn minimal_covers_of_the_textbook_examples() {
+        // Silberschatz, Korth and Sudarshan: the canonical cover of A -> BC, B -> C, A -> B, AB -> C
+        let fds = fd_set("ABC", &["A -> B, C", "B -> C", "A -> B", "A, B -> C"]);
+        assert_eq!(rendered(&fds.minimal_cover()), vec!["{A} -> B", "{B} -> C"]);
+
+        // Elmasri and Navathe: B -> A, D -> A, AB -> D has the minimal cover B -> D, D -> A
+        let fds = fd_set("ABD", &["B -> A", "D -> A", "A, B -> D"]);
+        assert_eq!(rendered(&fds.minimal_cover()), vec!["{D} -> A", "{B} -> D"]);
+
+        // Elmasri and Navathe: A -> BCDE, CD -> E reduce to A -> B, A -> C, A -> D, CD -> E
+        let fds = fd_set("ABCDE", &["A -> B, C, D, E", "C, D -> E"]);
+        assert_eq!(rendered(&fds.minimal_cover()), vec!["{A} -> B", "{A} -> C", "{A} -> D", "{C, D} -> E"]);
+
+        // trivial fds go, the cover is equivalent to the input
+        let fds = fd_set("ABCD", &["A, B -> A", "A -> B", "B -> C", "A -> C, D", "C -> D"]);
+        let cover = fds.minimal_cover();
+        assert_eq!(rendered(&cover), vec!["{A} -> B", "{B} -> C", "{C} -> D"]);
+        assert!(fds.iter().all(|fd| cover.implies(fd)) && cover.iter().all(|fd| fds.implies(fd)));
     }
 }
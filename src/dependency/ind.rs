@@ -0,0 +1,154 @@
+use crate::frontend::table::{Attribute, Table};
+use crate::dependency::result::{AlgorithmResult, Format, Statistics, write_csv, write_json, write_markdown};
+use crate::dependency::fd::Attributes;
+use std::fmt::{Display, Formatter};
+use std::rc::Rc;
+use serde::{Deserialize, Serialize, Serializer};
+use serde::ser::SerializeStruct;
+
+// dependent_table[dependent] ⊆ referenced_table[referenced], the attributes are paired by position
+#[derive(Deserialize, Serialize)]
+pub struct InclusionDependency {
+    dependent_table: String,
+    dependent: Attributes,
+    referenced_table: String,
+    referenced: Attributes
+}
+
+impl InclusionDependency {
+    pub fn arity(&self) -> usize {
+        self.dependent.0.len()
+    }
+}
+
+impl Display for InclusionDependency {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let columns = |attributes: &Attributes| -> String {
+            attributes.0.iter().map(|a| a.value.clone()).intersperse(", ".to_string()).collect()
+        };
+        write!(f, "{}[{}] ⊆ {}[{}]", self.dependent_table, columns(&self.dependent), self.referenced_table, columns(&self.referenced))
+    }
+}
+
+pub struct INDs {
+    inds: Vec<InclusionDependency>,
+    tables: Vec<(String, Rc<Vec<Attribute>>)>,
+    statistics: Statistics
+}
+
+impl INDs {
+    pub fn new(tables: &[Table]) -> INDs {
+        INDs {
+            inds: Vec::new(),
+            tables: tables.iter().map(|table| (table.table_name.clone(), Rc::clone(&table.attributes))).collect(),
+            statistics: Statistics::default()
+        }
+    }
+
+    // the name and attributes of every input table
+    pub fn tables(&self) -> &Vec<(String, Rc<Vec<Attribute>>)> {
+        &self.tables
+    }
+
+    pub fn set_statistics(&mut self, statistics: Statistics) {
+        self.statistics = statistics;
+    }
+
+    pub fn len(&self) -> usize {
+        self.inds.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inds.is_empty()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, InclusionDependency> {
+        self.inds.iter()
+    }
+
+    pub fn add(&mut self, ind: InclusionDependency) {
+        self.inds.push(ind);
+    }
+
+    pub fn add_from_index(&mut self, dependent_table: usize, dependent: &[usize], referenced_table: usize, referenced: &[usize]) {
+        let attributes = |table: usize, columns: &[usize]| -> Attributes {
+            Attributes(columns.iter().map(|index| self.tables[table].1.get(*index).unwrap().clone()).collect())
+        };
+
+        let ind = InclusionDependency {
+            dependent_table: self.tables[dependent_table].0.clone(),
+            dependent: attributes(dependent_table, dependent),
+            referenced_table: self.tables[referenced_table].0.clone(),
+            referenced: attributes(referenced_table, referenced)
+        };
+
+        self.add(ind);
+    }
+}
+
+impl Display for INDs {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let names: String = self.tables.iter().map(|t| t.0.clone()).intersperse(", ".to_string()).collect();
+        writeln!(f, "We have inclusion dependency set of the tables {}:", names)?;
+        for ind in self.inds.iter() {
+            writeln!(f, "IND: {}", ind)?;
+        }
+        Ok(())
+    }
+}
+
+impl Serialize for INDs {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct TableMeta<'a> {
+            table_name: &'a str,
+            attributes: &'a [Attribute]
+        }
+
+        let tables: Vec<TableMeta> = self.tables.iter().map(|(table_name, attributes)| TableMeta { table_name, attributes }).collect();
+        let mut state = serializer.serialize_struct("INDs", 3)?;
+        state.serialize_field("tables", &tables)?;
+        state.serialize_field("statistics", &self.statistics)?;
+        state.serialize_field("inds", &self.inds)?;
+        state.end()
+    }
+}
+
+impl AlgorithmResult for INDs {
+    type Dependency = InclusionDependency;
+
+    fn dependencies(&self) -> &[InclusionDependency] {
+        &self.inds
+    }
+
+    fn statistics(&self) -> &Statistics {
+        &self.statistics
+    }
+
+    fn save_as_file(&self, path: &str, format: Format) -> Result<(), std::io::Error> {
+        let columns = |attributes: &Attributes| -> String {
+            attributes.0.iter().map(|a| a.value.clone()).intersperse(";".to_string()).collect()
+        };
+
+        match format {
+            Format::Json => write_json(path, self),
+            Format::Csv => {
+                let rows = self.inds.iter().map(|ind| vec![
+                    ind.dependent_table.clone(),
+                    columns(&ind.dependent),
+                    ind.referenced_table.clone(),
+                    columns(&ind.referenced),
+                    ind.arity().to_string()
+                ]).collect();
+
+                write_csv(path, &["dependent_table", "dependent", "referenced_table", "referenced", "arity"], rows)
+            }
+            Format::Markdown => {
+                let rows = self.inds.iter().map(|ind| vec![ind.to_string(), ind.arity().to_string()]).collect();
+                let names: String = self.tables.iter().map(|t| t.0.clone()).intersperse(", ".to_string()).collect();
+
+                write_markdown(path, &format!("Inclusion dependencies of {}", names), &["Inclusion dependency", "Arity"], rows)
+            }
+        }
+    }
+}
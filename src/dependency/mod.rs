@@ -1,3 +1,12 @@
 pub mod fd;
 pub mod result;
 pub mod analysis;
+pub mod ucc;
+pub mod ind;
+pub mod normalize;
+pub mod workspace;
+pub mod syntax;
+pub mod validation;
+pub mod cfd;
+pub mod od;
+pub mod dc;
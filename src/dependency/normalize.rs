@@ -0,0 +1,377 @@
+use crate::dependency::fd::{Attributes, FDs};
+use crate::frontend::table::{Attribute, Table};
+use bit_set::BitSet;
+use std::fmt::{Display, Formatter};
+use std::rc::Rc;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NormalForm {
+    BCNF,
+    ThirdNF
+}
+
+impl NormalForm {
+    pub fn from_name(name: &str) -> Option<NormalForm> {
+        match name {
+            "bcnf" => Some(NormalForm::BCNF),
+            "3nf" => Some(NormalForm::ThirdNF),
+            _ => None
+        }
+    }
+}
+
+impl Display for NormalForm {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NormalForm::BCNF => write!(f, "BCNF"),
+            NormalForm::ThirdNF => write!(f, "3NF")
+        }
+    }
+}
+
+pub struct Relation {
+    pub name: String,
+    pub attributes: Attributes,
+    pub key: Attributes,
+    // the fds of the input set whose attributes all fall into this relation
+    pub preserved: Vec<String>
+}
+
+pub struct Decomposition {
+    pub normal_form: NormalForm,
+    pub table_name: String,
+    pub relations: Vec<Relation>,
+    pub lossless: bool,
+    // the fds of the input set no longer implied by the projections on the relations
+    pub lost: Vec<String>
+}
+
+// only the exact fds are used, an approximate fd would make the decomposition lossy on the data
+struct Schema {
+    attributes: Rc<Vec<Attribute>>,
+    fds: Vec<(BitSet, usize, String)>
+}
+
+impl Schema {
+    fn new(fds: &FDs) -> Schema {
+        let mut exact: Vec<(BitSet, usize, String)> = fds.iter()
+            .filter(|fd| fd.error() == 0.0)
+            .map(|fd| (fd.determinant_set(), fd.dependant_rank(), fd.to_string()))
+            .collect();
+        // the mined order follows the lattice hash maps, sort it so the proposal is reproducible
+        exact.sort_by_key(|(lhs, rhs, _)| (lhs.len(), lhs.iter().collect::<Vec<_>>(), *rhs));
+        Schema { attributes: Rc::clone(fds.attributes()), fds: exact }
+    }
+
+    fn all(&self) -> BitSet {
+        (0..self.attributes.len()).collect()
+    }
+
+    fn closure(&self, X: &BitSet) -> BitSet {
+        let mut closure = X.clone();
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for (lhs, rhs, _) in self.fds.iter() {
+                if !closure.contains(*rhs) && lhs.is_subset(&closure) {
+                    closure.insert(*rhs);
+                    changed = true;
+                }
+            }
+        }
+        closure
+    }
+
+    // a minimal subset of relation determining the whole relation, the leading columns are kept first
+    fn key_of(&self, relation: &BitSet) -> BitSet {
+        let mut key = relation.clone();
+        for a in relation.iter().collect::<Vec<_>>().into_iter().rev() {
+            key.remove(a);
+            if !self.closure(&key).is_superset(relation) {
+                key.insert(a);
+            }
+        }
+        key
+    }
+
+    fn attributes_of(&self, set: &BitSet) -> Attributes {
+        Attributes(set.iter().map(|index| self.attributes[index].clone()).collect())
+    }
+
+    fn decomposition(&self, normal_form: NormalForm, table_name: &str, relations: Vec<BitSet>) -> Decomposition {
+        // drop the relations contained in another one
+        let mut kept: Vec<BitSet> = Vec::new();
+        for (i, relation) in relations.iter().enumerate() {
+            let contained = relations.iter().enumerate().any(|(j, other)| {
+                i != j && relation.is_subset(other) && (relation != other || j < i)
+            });
+            if !contained {
+                kept.push(relation.clone());
+            }
+        }
+
+        let lossless = self.lossless(&kept);
+
+        let lost = self.fds.iter().filter(|(lhs, rhs, _)| {
+            !self.preserved_closure(&kept, lhs).contains(*rhs)
+        }).map(|fd| fd.2.clone()).collect();
+
+        let relations = kept.iter().enumerate().map(|(i, relation)| {
+            let preserved = self.fds.iter().filter(|(lhs, rhs, _)| {
+                lhs.is_subset(relation) && relation.contains(*rhs)
+            }).map(|fd| fd.2.clone()).collect();
+
+            Relation {
+                name: format!("{}_{}", table_name, i + 1),
+                attributes: self.attributes_of(relation),
+                key: self.attributes_of(&self.key_of(relation)),
+                preserved
+            }
+        }).collect();
+
+        Decomposition { normal_form, table_name: table_name.to_string(), relations, lossless, lost }
+    }
+
+    // the closure of X under the union of the fds projected on every relation
+    fn preserved_closure(&self, relations: &[BitSet], X: &BitSet) -> BitSet {
+        let mut result = X.clone();
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for relation in relations.iter() {
+                let mut part: BitSet = result.intersection(relation).collect();
+                part = self.closure(&part);
+                part.intersect_with(relation);
+                if !part.is_subset(&result) {
+                    result.union_with(&part);
+                    changed = true;
+                }
+            }
+        }
+        result
+    }
+
+    // the chase test, 0 is the distinguished symbol of a column
+    fn lossless(&self, relations: &[BitSet]) -> bool {
+        let n = self.attributes.len();
+        let mut tableau: Vec<Vec<usize>> = relations.iter().enumerate().map(|(i, relation)| {
+            (0..n).map(|j| if relation.contains(j) { 0 } else { i + 1 }).collect()
+        }).collect();
+
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for (lhs, rhs, _) in self.fds.iter() {
+                for i in 0..tableau.len() {
+                    for j in (i + 1)..tableau.len() {
+                        let agree = lhs.iter().all(|a| tableau[i][a] == tableau[j][a]);
+                        let (x, y) = (tableau[i][*rhs], tableau[j][*rhs]);
+                        if agree && x != y {
+                            let (keep, replace) = (x.min(y), x.max(y));
+                            for row in tableau.iter_mut() {
+                                if row[*rhs] == replace {
+                                    row[*rhs] = keep;
+                                }
+                            }
+                            changed = true;
+                        }
+                    }
+                }
+            }
+        }
+
+        tableau.iter().any(|row| row.iter().all(|symbol| *symbol == 0))
+    }
+}
+
+// splits on X -> X+ while some relation violates BCNF.
+// Tane reports every minimal fd, so a violation always shows up as an fd whose determinant lies in the relation
+pub fn bcnf(fds: &FDs) -> Decomposition {
+    let schema = Schema::new(fds);
+
+    let mut relations = Vec::new();
+    let mut stack = vec![schema.all()];
+
+    while let Some(relation) = stack.pop() {
+        let violation = schema.fds.iter().find(|(lhs, rhs, _)| {
+            lhs.is_subset(&relation) && relation.contains(*rhs) && !lhs.contains(*rhs)
+                && !schema.closure(lhs).is_superset(&relation)
+        });
+
+        if let Some((lhs, _, _)) = violation {
+            let determined: BitSet = schema.closure(lhs).intersection(&relation).collect();
+            let mut rest: BitSet = relation.difference(&determined).collect();
+            rest.union_with(lhs);
+            stack.push(rest);
+            stack.push(determined);
+        } else {
+            relations.push(relation);
+        }
+    }
+
+    schema.decomposition(NormalForm::BCNF, fds.table_name(), relations)
+}
+
+// Bernstein synthesis, one relation per determinant of the minimal cover plus a key relation
+pub fn third_normal_form(fds: &FDs) -> Decomposition {
+    let schema = Schema::new(fds);
+
+    let mut exact = FDs::with_attributes(fds.table_name().to_string(), Rc::clone(fds.attributes())).with_null_model(fds.null_model());
+    schema.fds.iter().for_each(|(lhs, rhs, _)| {
+        exact.add_from_index(lhs, *rhs, 0.0);
+    });
+    let cover = exact.minimal_cover();
+
+    // determinants with the same closure are merged into one relation
+    let mut relations: Vec<BitSet> = Vec::new();
+    let mut determinants: Vec<BitSet> = Vec::new();
+    for fd in cover.iter() {
+        let lhs = fd.determinant_set();
+        let closure = schema.closure(&lhs);
+        if let Some(index) = determinants.iter().position(|d| *d == closure) {
+            relations[index].union_with(&lhs);
+            relations[index].insert(fd.dependant_rank());
+        } else {
+            let mut relation = lhs.clone();
+            relation.insert(fd.dependant_rank());
+            determinants.push(closure);
+            relations.push(relation);
+        }
+    }
+
+    let all = schema.all();
+    if !relations.iter().any(|relation| schema.closure(relation).is_superset(&all)) {
+        relations.push(schema.key_of(&all));
+    }
+
+    schema.decomposition(NormalForm::ThirdNF, fds.table_name(), relations)
+}
+
+impl Decomposition {
+    // the columns are NOT NULL where the table has no nulls, a key over a nullable column is only UNIQUE
+    pub fn to_sql(&self, table: &Table) -> String {
+        let quote = |a: &Attribute| format!("\"{}\"", a.value.replace('"', "\"\""));
+        let nullable = |a: &Attribute| (0..table.row_num()).any(|row| table.is_null(row, a.rank));
+
+        self.relations.iter().map(|relation| {
+            let mut columns: Vec<String> = relation.attributes.0.iter().map(|a| {
+                if nullable(a) { format!("    {} TEXT", quote(a)) } else { format!("    {} TEXT NOT NULL", quote(a)) }
+            }).collect();
+            let key: String = relation.key.0.iter().map(quote).intersperse(", ".to_string()).collect();
+            if relation.key.0.iter().any(nullable) {
+                columns.push(format!("    UNIQUE ({})", key));
+            } else {
+                columns.push(format!("    PRIMARY KEY ({})", key));
+            }
+
+            format!("CREATE TABLE \"{}\" (\n{}\n);\n", relation.name, columns.join(",\n"))
+        }).collect::<Vec<_>>().join("\n")
+    }
+}
+
+impl Display for Decomposition {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{} decomposition of the table {} (lossless: {}, dependency preserving: {}):",
+            self.normal_form, self.table_name, self.lossless, self.lost.is_empty())?;
+        for relation in self.relations.iter() {
+            writeln!(f, "{}{} key {}", relation.name, relation.attributes, relation.key)?;
+            for fd in relation.preserved.iter() {
+                writeln!(f, "    preserves {}", fd)?;
+            }
+        }
+        for fd in self.lost.iter() {
+            writeln!(f, "lost {}", fd)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dependency::fd::FunctionalDependency;
+    use crate::testing;
+
+    fn fds(names: &[&str], fds: &[&str]) -> FDs {
+        let attributes: Vec<Attribute> = names.iter().enumerate().map(|(rank, name)| Attribute::new(rank, name.to_string())).collect();
+        let mut res = FDs::with_attributes("r".to_string(), Rc::new(attributes));
+        for fd in fds {
+            FunctionalDependency::parse(fd, &res.attributes().clone()).unwrap().into_iter().for_each(|fd| res.add(fd));
+        }
+        res
+    }
+
+    fn relations(decomposition: &Decomposition) -> Vec<(Vec<String>, Vec<String>)> {
+        let names = |attributes: &Attributes| attributes.0.iter().map(|a| a.value.clone()).collect::<Vec<_>>();
+        let mut relations: Vec<_> = decomposition.relations.iter().map(|r| (names(&r.attributes), names(&r.key))).collect();
+        relations.sort();
+        relations
+    }
+
+    fn set(ranks: &[usize]) -> BitSet {
+        ranks.iter().cloned().collect()
+    }
+
+    #[test]
+    fn bcnf_gives_up_the_address_fd() {
+        // the textbook street, city -> zip and zip -> city
+        let fds = fds(&["street", "city", "zip"], &["street, city -> zip", "zip -> city"]);
+        let bcnf = bcnf(&fds);
+        assert_eq!(relations(&bcnf), vec![
+            (vec!["city".to_string(), "zip".to_string()], vec!["zip".to_string()]),
+            (vec!["street".to_string(), "zip".to_string()], vec!["street".to_string(), "zip".to_string()])
+        ]);
+        assert!(bcnf.lossless);
+        assert_eq!(bcnf.lost, vec!["{street, city} -> zip"]);
+
+        // the relation is in 3NF already
+        let third = third_normal_form(&fds);
+        assert_eq!(relations(&third), vec![(vec!["street".to_string(), "city".to_string(), "zip".to_string()], vec!["street".to_string(), "city".to_string()])]);
+        assert!(third.lossless && third.lost.is_empty());
+    }
+
+    #[test]
+    fn third_normal_form_keeps_every_fd() {
+        // Elmasri and Navathe: the lots relation
+        let fds = fds(&["id", "county", "lot", "area", "price", "rate"],
+            &["id -> county, lot, area", "county, lot -> id, area", "county -> rate", "area -> price"]);
+        let third = third_normal_form(&fds);
+        assert!(third.lossless && third.lost.is_empty(), "{}", third);
+        let relations = relations(&third);
+        assert!(relations.contains(&(vec!["county".to_string(), "rate".to_string()], vec!["county".to_string()])), "{}", third);
+        assert!(relations.contains(&(vec!["area".to_string(), "price".to_string()], vec!["area".to_string()])), "{}", third);
+        assert_eq!(relations.len(), 3, "{}", third);
+
+        let bcnf = bcnf(&fds);
+        assert!(bcnf.lossless, "{}", bcnf);
+        // every relation is in BCNF, all its fds have a key as determinant
+        let schema = Schema::new(&fds);
+        for relation in bcnf.relations.iter() {
+            let columns: BitSet = relation.attributes.0.iter().map(|a| a.rank).collect();
+            for (lhs, rhs, _) in schema.fds.iter().filter(|(lhs, rhs, _)| lhs.is_subset(&columns) && columns.contains(*rhs)) {
+                assert!(lhs.contains(*rhs) || schema.closure(lhs).is_superset(&columns), "{}", bcnf);
+            }
+        }
+    }
+
+    #[test]
+    fn chase_finds_lossy_joins() {
+        let schema = Schema::new(&fds(&["a", "b", "c"], &["b -> c"]));
+        assert!(schema.lossless(&[set(&[0, 1]), set(&[1, 2])]));
+        assert!(!schema.lossless(&[set(&[0, 1]), set(&[2])]));
+        // the join over a, which determines nothing
+        assert!(!schema.lossless(&[set(&[0, 1]), set(&[0, 2])]));
+    }
+
+    #[test]
+    fn nullable_columns_in_the_ddl() {
+        let rows: Vec<Vec<String>> = [["1", "a", "x"], ["2", "", "y"], ["3", "a", "x"]].iter().map(|row| row.iter().map(|v| v.to_string()).collect()).collect();
+        let table = testing::table("t", &["id", "name", "code"], &rows);
+        let fds = fds(&["id", "name", "code"], &["id -> name, code", "code -> name"]);
+        let sql = third_normal_form(&fds).to_sql(&table);
+        assert!(sql.contains("\"id\" TEXT NOT NULL"), "{}", sql);
+        assert!(sql.contains("\"name\" TEXT,"), "{}", sql);
+        assert!(sql.contains("PRIMARY KEY (\"code\")"), "{}", sql);
+        assert!(!sql.contains("\"name\" TEXT NOT NULL"), "{}", sql);
+    }
+}
@@ -0,0 +1,183 @@
+use crate::frontend::table::{Attribute, Table};
+use crate::frontend::partition::NullModel;
+use crate::frontend::types::ColumnType;
+use crate::dependency::result::{AlgorithmResult, Format, Statistics, write_csv, write_json, write_markdown};
+use crate::dependency::fd::Attributes;
+use crate::dependency::syntax::quote_name;
+use bit_set::BitSet;
+use std::fmt::{Display, Formatter};
+use std::rc::Rc;
+use serde::{Serialize, Serializer};
+use serde::ser::SerializeStruct;
+
+// a canonical order dependency, every lexicographic od like [date] orders [invoice_no] splits into these.
+// the context X groups the rows the way the fd X -> ... would
+#[derive(Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum OrderDependency {
+    // X: [] -> A, A is constant within every class of X, the same as the fd X -> A
+    Constant { context: Attributes, dependant: Attribute },
+    // X: A ~ B, within every class of X no two rows are ordered one way by A and the other way by B
+    Compatible { context: Attributes, left: Attribute, right: Attribute }
+}
+
+impl OrderDependency {
+    pub fn context(&self) -> &Attributes {
+        match self {
+            OrderDependency::Constant { context, .. } => context,
+            OrderDependency::Compatible { context, .. } => context
+        }
+    }
+
+    pub fn is_constant(&self) -> bool {
+        matches!(self, OrderDependency::Constant { .. })
+    }
+}
+
+impl Display for OrderDependency {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OrderDependency::Constant { context, dependant } => write!(f, "{}: [] -> {}", context, quote_name(&dependant.value)),
+            OrderDependency::Compatible { context, left, right } => write!(f, "{}: {} ~ {}", context, quote_name(&left.value), quote_name(&right.value))
+        }
+    }
+}
+
+pub struct ODs {
+    ods: Vec<OrderDependency>,
+    table_name: String,
+    attributes: Rc<Vec<Attribute>>,
+    column_types: Vec<ColumnType>,
+    null_model: NullModel,
+    statistics: Statistics
+}
+
+impl ODs {
+    pub fn new(table: &Table, column_types: Vec<ColumnType>) -> ODs {
+        ODs { ods: Vec::new(), table_name: table.table_name.clone(), attributes: Rc::clone(&table.attributes), column_types, null_model: NullModel::Equal, statistics: Statistics::default() }
+    }
+
+    pub fn with_null_model(mut self, null_model: NullModel) -> ODs {
+        self.null_model = null_model;
+        self
+    }
+
+    pub fn null_model(&self) -> NullModel {
+        self.null_model
+    }
+
+    pub fn table_name(&self) -> &str {
+        &self.table_name
+    }
+
+    pub fn attributes(&self) -> &Rc<Vec<Attribute>> {
+        &self.attributes
+    }
+
+    // the type each column was compared as
+    pub fn column_types(&self) -> &[ColumnType] {
+        &self.column_types
+    }
+
+    pub fn set_statistics(&mut self, statistics: Statistics) {
+        self.statistics = statistics;
+    }
+
+    pub fn len(&self) -> usize {
+        self.ods.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ods.is_empty()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, OrderDependency> {
+        self.ods.iter()
+    }
+
+    fn context_from_index(&self, context: &BitSet) -> Attributes {
+        Attributes(context.iter().map(|index| self.attributes[index].clone()).collect())
+    }
+
+    pub fn add_constant(&mut self, context: &BitSet, a: usize) {
+        let od = OrderDependency::Constant { context: self.context_from_index(context), dependant: self.attributes[a].clone() };
+        self.ods.push(od);
+    }
+
+    pub fn add_compatible(&mut self, context: &BitSet, a: usize, b: usize) {
+        let od = OrderDependency::Compatible { context: self.context_from_index(context), left: self.attributes[a].clone(), right: self.attributes[b].clone() };
+        self.ods.push(od);
+    }
+}
+
+impl Display for ODs {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "We have order dependencies of the table {} ({}):", self.table_name, self.null_model)?;
+        let types: String = self.attributes.iter().zip(self.column_types.iter())
+            .map(|(a, t)| format!("{}: {}", quote_name(&a.value), t))
+            .intersperse(", ".to_string())
+            .collect();
+        writeln!(f, "Column types: {}", types)?;
+        for od in self.ods.iter() {
+            writeln!(f, "OD: {}", od)?;
+        }
+        Ok(())
+    }
+}
+
+impl Serialize for ODs {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("ODs", 6)?;
+        state.serialize_field("table_name", &self.table_name)?;
+        state.serialize_field("attributes", self.attributes.as_ref())?;
+        state.serialize_field("column_types", &self.column_types)?;
+        state.serialize_field("null_model", &self.null_model)?;
+        state.serialize_field("statistics", &self.statistics)?;
+        state.serialize_field("ods", &self.ods)?;
+        state.end()
+    }
+}
+
+impl AlgorithmResult for ODs {
+    type Dependency = OrderDependency;
+
+    fn dependencies(&self) -> &[OrderDependency] {
+        &self.ods
+    }
+
+    fn statistics(&self) -> &Statistics {
+        &self.statistics
+    }
+
+    fn save_as_file(&self, path: &str, format: Format) -> Result<(), std::io::Error> {
+        match format {
+            Format::Json => write_json(path, self),
+            Format::Csv => {
+                let rows = self.ods.iter().map(|od| {
+                    let (kind, left, right) = match od {
+                        OrderDependency::Constant { dependant, .. } => ("constant", String::new(), dependant.value.clone()),
+                        OrderDependency::Compatible { left, right, .. } => ("compatible", left.value.clone(), right.value.clone())
+                    };
+                    vec![
+                        self.table_name.clone(),
+                        kind.to_string(),
+                        od.context().0.iter().map(|a| a.value.clone()).intersperse(";".to_string()).collect(),
+                        left,
+                        right,
+                        self.null_model.to_string()
+                    ]
+                }).collect();
+
+                write_csv(path, &["table", "kind", "context", "left", "right", "null_model"], rows)
+            }
+            Format::Markdown => {
+                let rows = self.ods.iter().map(|od| vec![
+                    od.to_string(),
+                    if od.is_constant() { "constant".to_string() } else { "compatible".to_string() }
+                ]).collect();
+
+                write_markdown(path, &format!("Order dependencies of {} ({})", self.table_name, self.null_model), &["OD", "Kind"], rows)
+            }
+        }
+    }
+}
@@ -1,4 +1,168 @@
-pub trait AlgorithmResult {
-    fn display(&self);
-    fn save_as_file(&self) -> Result<(), std::io::Error>;
+use serde::Serialize;
+use std::fmt::{Display, Formatter};
+use std::fs::File;
+use std::io::{BufWriter, Error, Write};
+use std::path::Path;
+use std::time::Duration;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Json,
+    Csv,
+    Markdown
+}
+
+impl Format {
+    pub fn from_name(name: &str) -> Option<Format> {
+        match name {
+            "json" => Some(Format::Json),
+            "csv" => Some(Format::Csv),
+            "md" | "markdown" => Some(Format::Markdown),
+            _ => None
+        }
+    }
+
+    // json for a path without an extension, an unknown extension is an error rather than a json file under that name
+    pub fn from_path(path: &str) -> Result<Format, String> {
+        match Path::new(path).extension() {
+            None => Ok(Format::Json),
+            Some(ext) => Self::from_name(&ext.to_string_lossy())
+                .ok_or_else(|| format!("unknown extension .{} of {}, use .json, .csv or .md or give a --format", ext.to_string_lossy(), path))
+        }
+    }
+}
+
+// the report of a result is its Display, the library itself never prints.
+// serializing a result gives its metadata, statistics and dependencies
+pub trait AlgorithmResult: Display + Serialize {
+    type Dependency: Display + Serialize;
+
+    fn dependencies(&self) -> &[Self::Dependency];
+    fn statistics(&self) -> &Statistics;
+    fn save_as_file(&self, path: &str, format: Format) -> Result<(), std::io::Error>;
+}
+
+// the work of one level of a level-wise algorithm
+#[derive(Clone, Debug, Serialize)]
+pub struct LevelStatistics {
+    pub level: usize,
+    pub candidates: usize,
+    pub found: usize
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct Statistics {
+    pub elapsed: Duration,
+    pub levels: Vec<LevelStatistics>
+}
+
+impl Statistics {
+    pub fn add_level(&mut self, candidates: usize, found: usize) {
+        let level = self.levels.len() + 1;
+        self.levels.push(LevelStatistics { level, candidates, found });
+    }
+}
+
+impl Display for Statistics {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "elapsed: {:?}", self.elapsed)?;
+        for level in self.levels.iter() {
+            writeln!(f, "level {}: {} candidates, {} found", level.level, level.candidates, level.found)?;
+        }
+        Ok(())
+    }
+}
+
+pub fn write_json<T: serde::Serialize>(path: &str, value: &T) -> Result<(), Error> {
+    let writer = BufWriter::new(File::create(path)?);
+    serde_json::to_writer_pretty(writer, value).map_err(Error::other)
+}
+
+pub fn write_csv(path: &str, header: &[&str], rows: Vec<Vec<String>>) -> Result<(), Error> {
+    let mut writer = csv::Writer::from_path(path)?;
+    writer.write_record(header)?;
+    for row in rows {
+        writer.write_record(row)?;
+    }
+    writer.flush()
+}
+
+pub fn write_markdown(path: &str, title: &str, header: &[&str], rows: Vec<Vec<String>>) -> Result<(), Error> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    writeln!(writer, "# {}\n", title)?;
+    writeln!(writer, "| {} |", header.join(" | "))?;
+    writeln!(writer, "|{}", " --- |".repeat(header.len()))?;
+    for row in rows {
+        let row: Vec<String> = row.into_iter().map(|cell| cell.replace('|', "\\|")).collect();
+        writeln!(writer, "| {} |", row.join(" | "))?;
+    }
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    // a file in the temp dir, removed when dropped
+    struct TempFile(PathBuf);
+
+    impl TempFile {
+        fn new(name: &str) -> TempFile {
+            TempFile(std::env::temp_dir().join(format!("meta_miner_{}_{}", std::process::id(), name)))
+        }
+
+        fn path(&self) -> &str {
+            self.0.to_str().unwrap()
+        }
+    }
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.0);
+        }
+    }
+
+    #[test]
+    fn formats_by_name_and_extension() {
+        assert_eq!(Format::from_name("markdown"), Some(Format::Markdown));
+        assert_eq!(Format::from_path("out/fds.csv"), Ok(Format::Csv));
+        assert_eq!(Format::from_path("fds.md"), Ok(Format::Markdown));
+        assert_eq!(Format::from_path("fds"), Ok(Format::Json));
+        assert!(Format::from_path("fds.txt").unwrap_err().contains(".txt"));
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let file = TempFile::new("round_trip.json");
+        let mut statistics = Statistics::default();
+        statistics.add_level(3, 1);
+        write_json(file.path(), &statistics).unwrap();
+
+        let value: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(file.path()).unwrap()).unwrap();
+        assert_eq!(value["levels"][0]["level"], 1);
+        assert_eq!(value["levels"][0]["candidates"], 3);
+        assert_eq!(value["levels"][0]["found"], 1);
+    }
+
+    #[test]
+    fn csv_round_trip() {
+        let file = TempFile::new("round_trip.csv");
+        let rows = vec![vec!["{a, b}".to_string(), "say \"c\"".to_string()], vec!["{}".to_string(), "d".to_string()]];
+        write_csv(file.path(), &["determinant", "dependant"], rows.clone()).unwrap();
+
+        let mut reader = csv::Reader::from_path(file.path()).unwrap();
+        assert_eq!(reader.headers().unwrap(), vec!["determinant", "dependant"]);
+        let read: Vec<Vec<String>> = reader.records().map(|r| r.unwrap().iter().map(|v| v.to_string()).collect()).collect();
+        assert_eq!(read, rows);
+    }
+
+    #[test]
+    fn markdown_escapes_pipes() {
+        let file = TempFile::new("round_trip.md");
+        write_markdown(file.path(), "Fds of t", &["FD", "Error"], vec![vec!["{a|b} -> c".to_string(), "0.5".to_string()]]).unwrap();
+
+        let text = std::fs::read_to_string(file.path()).unwrap();
+        assert_eq!(text.lines().collect::<Vec<_>>(), vec!["# Fds of t", "", "| FD | Error |", "| --- | --- |", "| {a\\|b} -> c | 0.5 |"]);
+    }
 }
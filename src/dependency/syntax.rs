@@ -0,0 +1,298 @@
+use crate::frontend::table::Attribute;
+use std::borrow::Cow;
+use std::fmt::{Display, Formatter};
+
+// fd      := side ("->" | "→") side
+// side    := "{" [list] "}" | [list]
+// list    := item ("," item)*
+// item    := index | name | '"' name with "" for a quote '"'
+// names   := list
+// a bare number is the index of an attribute, quote it to use a numeric name
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    // the char offset in the input
+    pub position: usize,
+    pub message: String
+}
+
+impl ParseError {
+    fn new(position: usize, message: String) -> ParseError {
+        ParseError { position, message }
+    }
+
+    // the message with the input and a caret under the position
+    pub fn report(&self, input: &str) -> String {
+        format!("{}\n  {}\n  {}^", self, input.trim_end(), " ".repeat(self.position))
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at column {}", self.message, self.position + 1)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Term {
+    Index(usize),
+    Name(String)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Item {
+    pub term: Term,
+    pub position: usize
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsedFd {
+    pub determinant: Vec<Item>,
+    pub dependant: Vec<Item>
+}
+
+impl ParsedFd {
+    // the attributes of both sides, every side is sorted and without duplicates
+    pub fn resolve(&self, attributes: &[Attribute]) -> Result<(Vec<Attribute>, Vec<Attribute>), ParseError> {
+        let resolve_side = |items: &Vec<Item>| -> Result<Vec<Attribute>, ParseError> {
+            let mut side = Vec::new();
+            for item in items {
+                let attribute = match &item.term {
+                    Term::Index(index) => attributes.iter().find(|a| a.rank == *index)
+                        .ok_or_else(|| ParseError::new(item.position, format!("no attribute has the index {}", index)))?,
+                    Term::Name(name) => attributes.iter().find(|a| a.value == *name)
+                        .ok_or_else(|| ParseError::new(item.position, format!("unknown attribute `{}`", name)))?
+                };
+                side.push(attribute.clone());
+            }
+            side.sort();
+            side.dedup();
+            Ok(side)
+        };
+
+        Ok((resolve_side(&self.determinant)?, resolve_side(&self.dependant)?))
+    }
+}
+
+pub fn parse_fd(input: &str) -> Result<ParsedFd, ParseError> {
+    let mut parser = Parser { chars: input.chars().collect(), pos: 0 };
+
+    let determinant = parser.side()?;
+    parser.skip_whitespace();
+    if parser.eat_arrow() {
+        parser.skip_whitespace();
+    } else {
+        return Err(parser.expected("`->`"));
+    }
+
+    let dependant = parser.side()?;
+    if dependant.is_empty() {
+        return Err(ParseError::new(parser.pos, "the dependant side is empty".to_string()));
+    }
+
+    parser.skip_whitespace();
+    if parser.pos < parser.chars.len() {
+        return Err(parser.expected("the end of the fd"));
+    }
+
+    Ok(ParsedFd { determinant, dependant })
+}
+
+// the attribute names of a table, declared in the notation of the fds
+pub fn parse_names(input: &str) -> Result<Vec<String>, ParseError> {
+    let mut parser = Parser { chars: input.chars().collect(), pos: 0 };
+
+    let items = parser.list()?;
+    parser.skip_whitespace();
+    if parser.pos < parser.chars.len() {
+        return Err(parser.expected("`,` or the end of the names"));
+    }
+
+    items.into_iter().map(|item| match item.term {
+        Term::Name(name) => Ok(name),
+        Term::Index(index) => Err(ParseError::new(item.position, format!("a name is expected, quote \"{}\" to use it as one", index)))
+    }).collect()
+}
+
+// names are quoted when they would not be read back as the same name
+pub fn quote_name(name: &str) -> Cow<'_, str> {
+    let plain = !name.is_empty()
+        && !name.chars().all(|c| c.is_ascii_digit())
+        && !name.chars().any(|c| c.is_whitespace() || matches!(c, ',' | '{' | '}' | '"' | '→'))
+        && !name.contains("->");
+
+    if plain {
+        Cow::Borrowed(name)
+    } else {
+        Cow::Owned(format!("\"{}\"", name.replace('"', "\"\"")))
+    }
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).cloned()
+    }
+
+    fn at_arrow(&self) -> bool {
+        self.peek() == Some('→') || (self.peek() == Some('-') && self.chars.get(self.pos + 1) == Some(&'>'))
+    }
+
+    fn eat_arrow(&mut self) -> bool {
+        match self.peek() {
+            Some('→') => self.pos += 1,
+            Some('-') if self.at_arrow() => self.pos += 2,
+            _ => return false
+        }
+        true
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(|c| c.is_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    fn expected(&self, what: &str) -> ParseError {
+        match self.peek() {
+            Some(c) => ParseError::new(self.pos, format!("expected {}, found `{}`", what, c)),
+            None => ParseError::new(self.pos, format!("expected {}, found the end of the fd", what))
+        }
+    }
+
+    fn side(&mut self) -> Result<Vec<Item>, ParseError> {
+        self.skip_whitespace();
+        if self.peek() == Some('{') {
+            self.pos += 1;
+            self.skip_whitespace();
+            let items = if self.peek() == Some('}') { Vec::new() } else { self.list()? };
+            self.skip_whitespace();
+            if self.peek() != Some('}') {
+                return Err(self.expected("`,` or `}`"));
+            }
+            self.pos += 1;
+            Ok(items)
+        } else if self.at_arrow() || self.peek().is_none() {
+            Ok(Vec::new())
+        } else {
+            self.list()
+        }
+    }
+
+    fn list(&mut self) -> Result<Vec<Item>, ParseError> {
+        let mut items = vec![self.item()?];
+        loop {
+            self.skip_whitespace();
+            if self.peek() != Some(',') {
+                return Ok(items);
+            }
+            self.pos += 1;
+            items.push(self.item()?);
+        }
+    }
+
+    fn item(&mut self) -> Result<Item, ParseError> {
+        self.skip_whitespace();
+        let position = self.pos;
+
+        if self.peek() == Some('"') {
+            self.pos += 1;
+            let mut name = String::new();
+            loop {
+                match self.peek() {
+                    Some('"') if self.chars.get(self.pos + 1) == Some(&'"') => {
+                        name.push('"');
+                        self.pos += 2;
+                    }
+                    Some('"') => {
+                        self.pos += 1;
+                        return Ok(Item { term: Term::Name(name), position });
+                    }
+                    Some(c) => {
+                        name.push(c);
+                        self.pos += 1;
+                    }
+                    None => return Err(ParseError::new(position, "unterminated quoted name".to_string()))
+                }
+            }
+        }
+
+        let mut name = String::new();
+        while let Some(c) = self.peek() {
+            if c.is_whitespace() || matches!(c, ',' | '{' | '}' | '"') || self.at_arrow() {
+                break;
+            }
+            name.push(c);
+            self.pos += 1;
+        }
+
+        if name.is_empty() {
+            return Err(self.expected("an attribute"));
+        }
+
+        let term = if name.chars().all(|c| c.is_ascii_digit()) {
+            Term::Index(name.parse().map_err(|_| ParseError::new(position, format!("the index {} is too large", name)))?)
+        } else {
+            Term::Name(name)
+        };
+        Ok(Item { term, position })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attributes() -> Vec<Attribute> {
+        ["id", "first name", "zip", "2019", "a\"b"].iter().enumerate()
+            .map(|(rank, name)| Attribute::new(rank, name.to_string()))
+            .collect()
+    }
+
+    fn names(fd: &str) -> (Vec<String>, Vec<String>) {
+        let (lhs, rhs) = parse_fd(fd).unwrap().resolve(&attributes()).unwrap();
+        (lhs.into_iter().map(|a| a.value).collect(), rhs.into_iter().map(|a| a.value).collect())
+    }
+
+    #[test]
+    fn parses_every_notation() {
+        let expected = (vec!["id".to_string(), "zip".to_string()], vec!["first name".to_string()]);
+        assert_eq!(names("0,2 -> 1"), expected);
+        assert_eq!(names("id, zip -> \"first name\""), expected);
+        assert_eq!(names("{zip,id}->{\"first name\"}"), expected);
+        assert_eq!(names("{id, 2} → 1"), expected);
+        assert_eq!(names("{} -> id, \"2019\", \"a\"\"b\"").1, vec!["id", "2019", "a\"b"]);
+        assert_eq!(names("-> 3").1, vec!["2019"]);
+    }
+
+    #[test]
+    fn reports_positions() {
+        assert_eq!(parse_fd("id zip -> 1").unwrap_err().position, 3);
+        assert_eq!(parse_fd("id ->").unwrap_err().position, 5);
+        assert_eq!(parse_fd("{id, zip -> 1").unwrap_err().position, 9);
+        assert_eq!(parse_fd("id -> \"zip").unwrap_err().position, 6);
+        assert_eq!(parse_fd("id, cty -> zip").unwrap().resolve(&attributes()).unwrap_err().position, 4);
+        assert_eq!(parse_fd("id -> 9").unwrap().resolve(&attributes()).unwrap_err().position, 6);
+    }
+
+    #[test]
+    fn quoted_names_round_trip() {
+        for attribute in attributes() {
+            let fd = format!("{{id}} -> {}", quote_name(&attribute.value));
+            assert_eq!(names(&fd).1, vec![attribute.value]);
+        }
+    }
+
+    #[test]
+    fn parses_the_names_of_a_table() {
+        assert_eq!(parse_names("id, \"first name\",zip , \"2019\"").unwrap(), vec!["id", "first name", "zip", "2019"]);
+        assert_eq!(parse_names(" a ").unwrap(), vec!["a"]);
+        assert_eq!(parse_names("id, 2019").unwrap_err().position, 4);
+        assert_eq!(parse_names("id zip").unwrap_err().position, 3);
+        assert_eq!(parse_names("").unwrap_err().position, 0);
+    }
+}
@@ -0,0 +1,137 @@
+use crate::frontend::table::{Attribute, Table};
+use crate::frontend::partition::NullModel;
+use crate::dependency::result::{AlgorithmResult, Format, Statistics, write_csv, write_json, write_markdown};
+use crate::dependency::fd::Attributes;
+use bit_set::BitSet;
+use std::fmt::{Display, Formatter};
+use std::rc::Rc;
+use serde::{Deserialize, Serialize, Serializer};
+use serde::ser::SerializeStruct;
+
+#[derive(Deserialize, Serialize)]
+pub struct UniqueColumnCombination(pub Attributes);
+
+impl Display for UniqueColumnCombination {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+pub struct UCCs {
+    uccs: Vec<UniqueColumnCombination>,
+    table_name: String,
+    attributes: Rc<Vec<Attribute>>,
+    null_model: NullModel,
+    statistics: Statistics
+}
+
+impl UCCs {
+    pub fn new(table: &Table) -> UCCs {
+        UCCs {uccs: Vec::new(), table_name: table.table_name.clone(), attributes: Rc::clone(&table.attributes), null_model: NullModel::Equal, statistics: Statistics::default()}
+    }
+
+    pub fn with_null_model(mut self, null_model: NullModel) -> UCCs {
+        self.null_model = null_model;
+        self
+    }
+
+    pub fn null_model(&self) -> NullModel {
+        self.null_model
+    }
+
+    pub fn table_name(&self) -> &str {
+        &self.table_name
+    }
+
+    pub fn attributes(&self) -> &Rc<Vec<Attribute>> {
+        &self.attributes
+    }
+
+    pub fn set_statistics(&mut self, statistics: Statistics) {
+        self.statistics = statistics;
+    }
+
+    pub fn len(&self) -> usize {
+        self.uccs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.uccs.is_empty()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, UniqueColumnCombination> {
+        self.uccs.iter()
+    }
+
+    pub fn add(&mut self, ucc: UniqueColumnCombination) {
+        self.uccs.push(ucc);
+    }
+
+    pub fn add_from_index(&mut self, X: &BitSet) {
+        let mut columns: Vec<_> = X.iter().map(|index| {
+            self.attributes.get(index).unwrap().clone()
+        }).collect();
+
+        columns.sort();
+
+        self.add(UniqueColumnCombination(Attributes(columns)));
+    }
+}
+
+impl Display for UCCs {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "We have minimal unique column combinations of the table {} ({}):", self.table_name, self.null_model)?;
+        for ucc in self.uccs.iter() {
+            writeln!(f, "UCC: {}", ucc)?;
+        }
+        Ok(())
+    }
+}
+
+impl Serialize for UCCs {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("UCCs", 5)?;
+        state.serialize_field("table_name", &self.table_name)?;
+        state.serialize_field("attributes", self.attributes.as_ref())?;
+        state.serialize_field("null_model", &self.null_model)?;
+        state.serialize_field("statistics", &self.statistics)?;
+        state.serialize_field("uccs", &self.uccs)?;
+        state.end()
+    }
+}
+
+impl AlgorithmResult for UCCs {
+    type Dependency = UniqueColumnCombination;
+
+    fn dependencies(&self) -> &[UniqueColumnCombination] {
+        &self.uccs
+    }
+
+    fn statistics(&self) -> &Statistics {
+        &self.statistics
+    }
+
+    fn save_as_file(&self, path: &str, format: Format) -> Result<(), std::io::Error> {
+        match format {
+            Format::Json => write_json(path, self),
+            Format::Csv => {
+                let rows = self.uccs.iter().map(|ucc| vec![
+                    self.table_name.clone(),
+                    ucc.0.0.iter().map(|a| a.value.clone()).intersperse(";".to_string()).collect(),
+                    ucc.0.0.iter().map(|a| a.rank.to_string()).intersperse(";".to_string()).collect(),
+                    self.null_model.to_string()
+                ]).collect();
+
+                write_csv(path, &["table", "columns", "ranks", "null_model"], rows)
+            }
+            Format::Markdown => {
+                let rows = self.uccs.iter().map(|ucc| vec![
+                    ucc.0.0.iter().map(|a| format!("{} ({})", a.value, a.rank)).intersperse(", ".to_string()).collect(),
+                    ucc.0.0.len().to_string()
+                ]).collect();
+
+                write_markdown(path, &format!("Unique column combinations of {} ({})", self.table_name, self.null_model), &["Columns", "Size"], rows)
+            }
+        }
+    }
+}
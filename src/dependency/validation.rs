@@ -0,0 +1,192 @@
+use crate::frontend::table::{Attribute, Table};
+use crate::frontend::partition::NullModel;
+use crate::dependency::result::{AlgorithmResult, Format, Statistics, write_csv, write_json, write_markdown};
+use crate::dependency::fd::FunctionalDependency;
+use crate::dependency::syntax::quote_name;
+use std::fmt::{Display, Formatter};
+use std::rc::Rc;
+use serde::{Serialize, Serializer};
+use serde::ser::SerializeStruct;
+
+// the rows of one determinant value that disagree on the dependant,
+// grouped by their dependant value. row indices start at 0 with the first data row
+#[derive(Clone, Debug, Serialize)]
+pub struct ViolatingGroup {
+    pub determinant: Vec<String>,
+    pub dependant: Vec<(String, Vec<usize>)>
+}
+
+impl ViolatingGroup {
+    pub fn rows(&self) -> Vec<usize> {
+        let mut rows: Vec<usize> = self.dependant.iter().flat_map(|(_, rows)| rows.iter().cloned()).collect();
+        rows.sort_unstable();
+        rows
+    }
+}
+
+#[derive(Serialize)]
+pub struct FdValidation {
+    pub fd: FunctionalDependency,
+    pub holds: bool,
+    // the fraction of rows to remove for the fd to hold
+    pub g3: f64,
+    // the fraction of ordered row pairs violating the fd
+    pub g1: f64,
+    // all the violating groups, even when only the first ones are kept
+    pub violating_groups: usize,
+    pub violations: Vec<ViolatingGroup>
+}
+
+impl Display for FdValidation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.holds {
+            return write!(f, "{} holds", self.fd);
+        }
+
+        write!(f, "{} is violated, g3 {:.4}, g1 {:.4}, {} violating groups", self.fd, self.g3, self.g1, self.violating_groups)?;
+        let (determinant, dependant) = self.fd.disintegrate();
+        for group in self.violations.iter() {
+            let lhs: String = determinant.iter().zip(group.determinant.iter())
+                .map(|(a, value)| format!("{} = {:?}", quote_name(&a.value), value))
+                .intersperse(", ".to_string())
+                .collect();
+            write!(f, "\n    {}:", if lhs.is_empty() { "every row".to_string() } else { lhs })?;
+            for (value, rows) in group.dependant.iter() {
+                write!(f, " {} = {:?} at rows {:?};", quote_name(&dependant[0].value), value, rows)?;
+            }
+        }
+        if self.violations.len() < self.violating_groups {
+            write!(f, "\n    ... {} more groups", self.violating_groups - self.violations.len())?;
+        }
+        Ok(())
+    }
+}
+
+pub struct Validations {
+    validations: Vec<FdValidation>,
+    table_name: String,
+    attributes: Rc<Vec<Attribute>>,
+    null_model: NullModel,
+    statistics: Statistics
+}
+
+impl Validations {
+    pub fn new(table: &Table) -> Validations {
+        Validations { validations: Vec::new(), table_name: table.table_name.clone(), attributes: Rc::clone(&table.attributes), null_model: NullModel::Equal, statistics: Statistics::default() }
+    }
+
+    pub fn with_null_model(mut self, null_model: NullModel) -> Validations {
+        self.null_model = null_model;
+        self
+    }
+
+    pub fn null_model(&self) -> NullModel {
+        self.null_model
+    }
+
+    pub fn table_name(&self) -> &str {
+        &self.table_name
+    }
+
+    pub fn attributes(&self) -> &Rc<Vec<Attribute>> {
+        &self.attributes
+    }
+
+    pub fn set_statistics(&mut self, statistics: Statistics) {
+        self.statistics = statistics;
+    }
+
+    pub fn len(&self) -> usize {
+        self.validations.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.validations.is_empty()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, FdValidation> {
+        self.validations.iter()
+    }
+
+    pub fn add(&mut self, validation: FdValidation) {
+        self.validations.push(validation);
+    }
+
+    // true when every validated fd holds
+    pub fn all_hold(&self) -> bool {
+        self.validations.iter().all(|v| v.holds)
+    }
+}
+
+impl Display for Validations {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let held = self.validations.iter().filter(|v| v.holds).count();
+        writeln!(f, "Validation of {} fds on the table {} ({}), {} hold:", self.validations.len(), self.table_name, self.null_model, held)?;
+        for validation in self.validations.iter() {
+            writeln!(f, "{}", validation)?;
+        }
+        Ok(())
+    }
+}
+
+impl Serialize for Validations {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("Validations", 5)?;
+        state.serialize_field("table_name", &self.table_name)?;
+        state.serialize_field("attributes", self.attributes.as_ref())?;
+        state.serialize_field("null_model", &self.null_model)?;
+        state.serialize_field("statistics", &self.statistics)?;
+        state.serialize_field("validations", &self.validations)?;
+        state.end()
+    }
+}
+
+impl AlgorithmResult for Validations {
+    type Dependency = FdValidation;
+
+    fn dependencies(&self) -> &[FdValidation] {
+        &self.validations
+    }
+
+    fn statistics(&self) -> &Statistics {
+        &self.statistics
+    }
+
+    fn save_as_file(&self, path: &str, format: Format) -> Result<(), std::io::Error> {
+        let rows_of = |v: &FdValidation| -> String {
+            v.violations.iter()
+                .map(|group| group.rows().iter().map(|row| row.to_string()).intersperse(" ".to_string()).collect::<String>())
+                .intersperse(";".to_string())
+                .collect()
+        };
+
+        match format {
+            Format::Json => write_json(path, self),
+            Format::Csv => {
+                let rows = self.validations.iter().map(|v| vec![
+                    self.table_name.clone(),
+                    v.fd.to_string(),
+                    v.holds.to_string(),
+                    v.g3.to_string(),
+                    v.g1.to_string(),
+                    v.violating_groups.to_string(),
+                    rows_of(v),
+                    self.null_model.to_string()
+                ]).collect();
+
+                write_csv(path, &["table", "fd", "holds", "g3", "g1", "violating_groups", "violating_rows", "null_model"], rows)
+            }
+            Format::Markdown => {
+                let rows = self.validations.iter().map(|v| vec![
+                    v.fd.to_string(),
+                    if v.holds { "yes".to_string() } else { "no".to_string() },
+                    format!("{:.4}", v.g3),
+                    format!("{:.4}", v.g1),
+                    rows_of(v)
+                ]).collect();
+
+                write_markdown(path, &format!("Validation of the fds of {} ({})", self.table_name, self.null_model), &["FD", "Holds", "g3", "g1", "Violating rows"], rows)
+            }
+        }
+    }
+}
@@ -0,0 +1,234 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::io::{BufReader, BufWriter, Error, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+
+pub const DEFAULT_WORKSPACE: &str = ".meta_miner";
+pub const DEFAULT_DATASET: &str = "default";
+
+const LOCK_FILE: &str = ".lock";
+
+// a dataset is one directory right below the root
+fn check_dataset(dataset: &str) -> Result<(), Error> {
+    if dataset.is_empty() || dataset.contains(['/', '\\']) || dataset.starts_with('.') {
+        return Err(Error::new(ErrorKind::InvalidInput, format!("`{}` is not a valid dataset name", dataset)));
+    }
+    Ok(())
+}
+
+// the analysis state of one dataset, kept in <root>/<dataset>/<name>.json.
+// nothing is written unless save is called, and every save replaces the file atomically
+pub struct Workspace {
+    dir: PathBuf,
+    read_only: bool,
+    // the lock is held as long as the workspace is open
+    _lock: File
+}
+
+impl Workspace {
+    // exclusive access, for the commands changing the collections
+    pub fn open(root: &Path, dataset: &str) -> Result<Workspace, Error> {
+        Workspace::open_with(root, dataset, false)
+    }
+
+    // shared access, any number of readers may run together but no writer
+    pub fn open_read_only(root: &Path, dataset: &str) -> Result<Workspace, Error> {
+        Workspace::open_with(root, dataset, true)
+    }
+
+    // shared access to a workspace some command already created, None leaves the disk untouched
+    pub fn open_existing(root: &Path, dataset: &str) -> Result<Option<Workspace>, Error> {
+        check_dataset(dataset)?;
+        if !root.join(dataset).is_dir() {
+            return Ok(None);
+        }
+        Workspace::open_with(root, dataset, true).map(Some)
+    }
+
+    fn open_with(root: &Path, dataset: &str, read_only: bool) -> Result<Workspace, Error> {
+        check_dataset(dataset)?;
+
+        let dir = root.join(dataset);
+        fs::create_dir_all(&dir)?;
+
+        let lock = OpenOptions::new().create(true).truncate(false).write(true).open(dir.join(LOCK_FILE))?;
+        let locked = if read_only { lock.try_lock_shared() } else { lock.try_lock() };
+        match locked {
+            Ok(()) => {}
+            Err(TryLockError::WouldBlock) => {
+                return Err(Error::new(ErrorKind::WouldBlock, format!("the workspace {} is in use by another process", dir.display())));
+            }
+            Err(TryLockError::Error(e)) => return Err(e)
+        }
+
+        log::debug!("Open workspace {} (read only: {})", dir.display(), read_only);
+        Ok(Workspace { dir, read_only, _lock: lock })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn path(&self, name: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", name))
+    }
+
+    // None when nothing was saved under the name yet
+    pub fn load<T: DeserializeOwned>(&self, name: &str) -> Result<Option<T>, Error> {
+        let f = match File::open(self.path(name)) {
+            Ok(f) => f,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e)
+        };
+
+        let value = serde_json::from_reader(BufReader::new(f))
+            .map_err(|e| Error::new(ErrorKind::InvalidData, format!("{}: {}", self.path(name).display(), e)))?;
+        Ok(Some(value))
+    }
+
+    // written next to the target first, so a crash never leaves half a file behind
+    pub fn save<T: Serialize>(&self, name: &str, value: &T) -> Result<(), Error> {
+        self.write_report(&format!("{}.json", name), |tmp| {
+            let mut writer = BufWriter::new(File::create(tmp)?);
+            serde_json::to_writer(&mut writer, value).map_err(Error::other)?;
+            writer.flush()?;
+            writer.get_ref().sync_all()
+        })?;
+        Ok(())
+    }
+
+    // any other file of the workspace, e.g. the markdown report, replaced the same way as the collections.
+    // write gets the temporary path, the final path is returned
+    pub fn write_report<F: FnOnce(&Path) -> Result<(), Error>>(&self, file_name: &str, write: F) -> Result<PathBuf, Error> {
+        if self.read_only {
+            return Err(Error::new(ErrorKind::PermissionDenied, format!("the workspace {} is opened read only", self.dir.display())));
+        }
+
+        let path = self.dir.join(file_name);
+        let tmp = self.dir.join(format!(".{}.tmp", file_name));
+        if let Err(e) = write(&tmp) {
+            let _ = fs::remove_file(&tmp);
+            return Err(e);
+        }
+        fs::rename(&tmp, &path)?;
+
+        log::trace!("Save {}", path.display());
+        Ok(path)
+    }
+
+    // the names of everything saved in the workspace
+    pub fn names(&self) -> Result<Vec<String>, Error> {
+        let mut names = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            let name = path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+            if let Some(name) = name.strip_suffix(".json") {
+                if !name.starts_with('.') {
+                    names.push(name.to_string());
+                }
+            }
+        }
+        names.sort();
+        Ok(names)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    // a root in the temp dir, removed with everything below it when dropped
+    struct TempRoot(PathBuf);
+
+    impl TempRoot {
+        fn new(name: &str) -> TempRoot {
+            let root = std::env::temp_dir().join(format!("meta_miner_{}_{}", std::process::id(), name));
+            let _ = fs::remove_dir_all(&root);
+            TempRoot(root)
+        }
+    }
+
+    impl Drop for TempRoot {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn saves_and_loads_by_name() {
+        let root = TempRoot::new("workspace_round_trip");
+        let workspace = Workspace::open(&root.0, "people").unwrap();
+        assert_eq!(workspace.dir(), root.0.join("people"));
+        assert_eq!(workspace.load::<Vec<String>>("mined").unwrap(), None);
+
+        let fds = vec!["a -> b".to_string(), "b, c -> d".to_string()];
+        workspace.save("mined", &fds).unwrap();
+        workspace.save("attributes", &HashMap::from([("a", 0)])).unwrap();
+        assert_eq!(workspace.load::<Vec<String>>("mined").unwrap(), Some(fds));
+
+        // the lock and no temporary file are listed
+        assert_eq!(workspace.names().unwrap(), vec!["attributes", "mined"]);
+    }
+
+    #[test]
+    fn broken_files_name_their_path() {
+        let root = TempRoot::new("workspace_broken");
+        let workspace = Workspace::open(&root.0, DEFAULT_DATASET).unwrap();
+        fs::write(workspace.dir().join("mined.json"), "[\"a -> b\"").unwrap();
+
+        let e = workspace.load::<Vec<String>>("mined").unwrap_err();
+        assert_eq!(e.kind(), ErrorKind::InvalidData);
+        assert!(e.to_string().contains("mined.json"));
+    }
+
+    #[test]
+    fn readers_share_and_writers_exclude() {
+        let root = TempRoot::new("workspace_locks");
+        {
+            let writer = Workspace::open(&root.0, DEFAULT_DATASET).unwrap();
+            writer.save("new", &Vec::<String>::new()).unwrap();
+            assert_eq!(Workspace::open_read_only(&root.0, DEFAULT_DATASET).err().map(|e| e.kind()), Some(ErrorKind::WouldBlock));
+        }
+
+        let reader = Workspace::open_read_only(&root.0, DEFAULT_DATASET).unwrap();
+        let other = Workspace::open_existing(&root.0, DEFAULT_DATASET).unwrap().unwrap();
+        assert_eq!(other.load::<Vec<String>>("new").unwrap(), Some(Vec::new()));
+        assert_eq!(reader.save("new", &Vec::<String>::new()).unwrap_err().kind(), ErrorKind::PermissionDenied);
+        assert_eq!(Workspace::open(&root.0, DEFAULT_DATASET).err().map(|e| e.kind()), Some(ErrorKind::WouldBlock));
+    }
+
+    #[test]
+    fn reports_are_replaced_whole() {
+        let root = TempRoot::new("workspace_report");
+        {
+            let writer = Workspace::open(&root.0, DEFAULT_DATASET).unwrap();
+            let path = writer.write_report("analysis.md", |tmp| fs::write(tmp, "# fds")).unwrap();
+            assert_eq!(fs::read_to_string(&path).unwrap(), "# fds");
+
+            // a failed write leaves the old report and no temporary file
+            let e = writer.write_report("analysis.md", |tmp| {
+                fs::write(tmp, "# half")?;
+                Err(Error::other("broken"))
+            }).unwrap_err();
+            assert_eq!(e.to_string(), "broken");
+            assert_eq!(fs::read_to_string(&path).unwrap(), "# fds");
+            assert!(!writer.dir().join(".analysis.md.tmp").exists());
+        }
+
+        let reader = Workspace::open_read_only(&root.0, DEFAULT_DATASET).unwrap();
+        assert_eq!(reader.write_report("analysis.md", |tmp| fs::write(tmp, "")).unwrap_err().kind(), ErrorKind::PermissionDenied);
+    }
+
+    #[test]
+    fn missing_workspaces_are_not_created() {
+        let root = TempRoot::new("workspace_missing");
+        assert!(Workspace::open_existing(&root.0, DEFAULT_DATASET).unwrap().is_none());
+        assert!(!root.0.exists());
+
+        for dataset in ["", "a/b", ".hidden"] {
+            assert_eq!(Workspace::open(&root.0, dataset).err().map(|e| e.kind()), Some(ErrorKind::InvalidInput));
+        }
+    }
+}
@@ -1,3 +1,4 @@
 pub mod table;
 pub mod parser;
-pub mod partition;
\ No newline at end of file
+pub mod partition;
+pub mod types;
\ No newline at end of file
@@ -1,32 +1,223 @@
 extern crate csv;
 
-use csv::Reader;
-use regex::Regex;
+use csv::ReaderBuilder;
 use crate::frontend::table::{Table, Attribute};
-use std::io::{Error};
+use std::fs::File;
+use std::io::{BufRead, BufReader, Error, Read};
+use std::path::Path;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Encoding {
+    // a leading byte order mark is skipped
+    Utf8,
+    Latin1
+}
+
+impl Encoding {
+    pub fn from_name(name: &str) -> Option<Encoding> {
+        match name.to_lowercase().as_str() {
+            "utf8" | "utf-8" => Some(Encoding::Utf8),
+            "latin1" | "latin-1" | "iso-8859-1" => Some(Encoding::Latin1),
+            _ => None
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct CsvOptions {
+    pub delimiter: u8,
+    pub quote: u8,
+    // quotes are escaped by doubling them when no escape char is set
+    pub escape: Option<u8>,
+    pub comment: Option<u8>,
+    pub has_header: bool,
+    pub encoding: Encoding,
+    pub limit: Option<usize>,
+    // fields equal to one of these are loaded as nulls, the empty field by default
+    pub null_tokens: Vec<String>
+}
+
+impl Default for CsvOptions {
+    fn default() -> CsvOptions {
+        CsvOptions {
+            delimiter: b',',
+            quote: b'"',
+            escape: None,
+            comment: None,
+            has_header: true,
+            encoding: Encoding::Utf8,
+            limit: None,
+            null_tokens: vec![String::new()]
+        }
+    }
+}
 
 pub fn load_from_file(filename: &str) -> Result<Table, Error> {
-    let mut reader = Reader::from_path(filename)?;
+    load_from_file_with(filename, &CsvOptions::default())
+}
 
-    let header = reader.headers()?;
+pub fn load_from_file_with(filename: &str, options: &CsvOptions) -> Result<Table, Error> {
+    // tables are named after the file stem, so several inputs can be told apart
+    let table_name = Path::new(filename).file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or(filename.to_string());
+
+    load_from_reader(table_name, File::open(filename)?, options)
+}
 
-    let re = Regex::new(r"(?P<n>).csv").unwrap();
-    let table_name = re.replace(filename, "$n").to_string();
+pub fn load_from_reader<R: Read>(table_name: String, reader: R, options: &CsvOptions) -> Result<Table, Error> {
+    let reader: Box<dyn Read> = match options.encoding {
+        Encoding::Utf8 => Box::new(SkipBom::new(reader)),
+        Encoding::Latin1 => Box::new(Latin1Reader::new(reader))
+    };
 
+    let mut reader = ReaderBuilder::new()
+        .delimiter(options.delimiter)
+        .quote(options.quote)
+        .double_quote(options.escape.is_none())
+        .escape(options.escape)
+        .comment(options.comment)
+        .has_headers(options.has_header)
+        .from_reader(reader);
+
+    // without a header row this is the first record, which is not skipped
+    let header = reader.headers()?;
     let mut metadata = Vec::new();
-    let mut rank = 0;
-    for meta in header {
-        metadata.push(Attribute::new(rank, meta.to_string()));
-        rank += 1;
+    for (rank, meta) in header.iter().enumerate() {
+        let name = if options.has_header { meta.to_string() } else { format!("column_{}", rank) };
+        metadata.push(Attribute::new(rank, name));
     }
 
-    let mut table = Table::new(table_name, metadata);
+    let mut table = Table::with_null_tokens(table_name, metadata, options.null_tokens.clone());
 
-    for record in reader.records() {
+    let limit = options.limit.unwrap_or(usize::MAX);
+    for record in reader.records().take(limit) {
         let record = record?;
         let data = record.iter().map(|s| -> String { s.to_string() }).collect::<Vec<String>>();
         table.add_entry(data);
     }
 
     Ok(table)
-}
\ No newline at end of file
+}
+
+struct SkipBom<R: Read> {
+    inner: BufReader<R>,
+    checked: bool
+}
+
+impl<R: Read> SkipBom<R> {
+    fn new(inner: R) -> SkipBom<R> {
+        SkipBom { inner: BufReader::new(inner), checked: false }
+    }
+}
+
+impl<R: Read> Read for SkipBom<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if !self.checked {
+            self.checked = true;
+            if self.inner.fill_buf()?.starts_with(&[0xEF, 0xBB, 0xBF]) {
+                self.inner.consume(3);
+            }
+        }
+        self.inner.read(buf)
+    }
+}
+
+// every byte is one code point, so the text is re-encoded as utf-8 on the fly
+struct Latin1Reader<R: Read> {
+    inner: BufReader<R>,
+    pending: Vec<u8>
+}
+
+impl<R: Read> Latin1Reader<R> {
+    fn new(inner: R) -> Latin1Reader<R> {
+        Latin1Reader { inner: BufReader::new(inner), pending: Vec::new() }
+    }
+}
+
+impl<R: Read> Read for Latin1Reader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.pending.is_empty() {
+            let mut raw = vec![0u8; buf.len().max(2) / 2];
+            let n = self.inner.read(&mut raw)?;
+            for byte in raw[..n].iter() {
+                let mut encoded = [0u8; 2];
+                self.pending.extend_from_slice((*byte as char).encode_utf8(&mut encoded).as_bytes());
+            }
+        }
+
+        let n = self.pending.len().min(buf.len());
+        buf[..n].copy_from_slice(&self.pending[..n]);
+        self.pending.drain(..n);
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load(input: &[u8], options: &CsvOptions) -> Table {
+        load_from_reader("t".to_string(), input, options).unwrap()
+    }
+
+    fn names(table: &Table) -> Vec<String> {
+        table.attributes.iter().map(|a| a.value.clone()).collect()
+    }
+
+    fn rows(table: &Table) -> Vec<Vec<String>> {
+        (0..table.row_num()).map(|row| table.row(row).iter().map(|v| v.to_string()).collect()).collect()
+    }
+
+    #[test]
+    fn reads_the_dialect() {
+        let options = CsvOptions { delimiter: b';', quote: b'\'', escape: Some(b'\\'), comment: Some(b'#'), ..CsvOptions::default() };
+        let table = load(b"a;b\n# skipped\n'x;y';'it\\'s'\n1;2\n", &options);
+        assert_eq!(names(&table), vec!["a", "b"]);
+        assert_eq!(rows(&table), vec![vec!["x;y", "it's"], vec!["1", "2"]]);
+
+        // quotes are doubled by default, tabs as delimiter
+        let options = CsvOptions { delimiter: b'\t', ..CsvOptions::default() };
+        assert_eq!(rows(&load(b"a\tb\n\"say \"\"hi\"\"\"\t2\n", &options)), vec![vec!["say \"hi\"", "2"]]);
+    }
+
+    #[test]
+    fn headerless_files_keep_their_first_row() {
+        let options = CsvOptions { has_header: false, limit: Some(2), ..CsvOptions::default() };
+        let table = load(b"1,2\n3,4\n5,6\n", &options);
+        assert_eq!(names(&table), vec!["column_0", "column_1"]);
+        assert_eq!(rows(&table), vec![vec!["1", "2"], vec!["3", "4"]]);
+    }
+
+    #[test]
+    fn null_tokens_are_nulls() {
+        let options = CsvOptions { null_tokens: vec![String::new(), "NA".to_string()], ..CsvOptions::default() };
+        let table = load(b"a,b\n,NA\nNA,x\n", &options);
+        assert!(table.is_null(0, 0) && table.is_null(0, 1) && table.is_null(1, 0));
+        assert!(!table.is_null(1, 1));
+    }
+
+    #[test]
+    fn skips_the_byte_order_mark() {
+        let table = load(b"\xEF\xBB\xBFid,name\n1,caf\xC3\xA9\n", &CsvOptions::default());
+        assert_eq!(names(&table), vec!["id", "name"]);
+        assert_eq!(rows(&table), vec![vec!["1", "café"]]);
+        assert_eq!(load(b"", &CsvOptions::default()).row_num(), 0);
+    }
+
+    #[test]
+    fn decodes_latin1() {
+        let options = CsvOptions { encoding: Encoding::Latin1, ..CsvOptions::default() };
+        let table = load(b"nom,ville\nZo\xEB,M\xFCnchen\n", &options);
+        assert_eq!(rows(&table), vec![vec!["Zoë", "München"]]);
+
+        // a two byte char split across reads
+        let mut reader = Latin1Reader::new(&b"\xE9t\xE9"[..]);
+        let mut decoded = Vec::new();
+        let mut buf = [0u8; 1];
+        while reader.read(&mut buf).unwrap() > 0 {
+            decoded.push(buf[0]);
+        }
+        assert_eq!(String::from_utf8(decoded).unwrap(), "été");
+    }
+}
@@ -1,56 +1,226 @@
 use crate::frontend::table::{Attribute, Table};
+use crate::frontend::types::ColumnType;
+use bit_set::BitSet;
+use serde::{Deserialize, Serialize};
 use std::fmt::{Display, Formatter};
 use std::ops::Mul;
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
+
+// equivalence classes of size one are stripped, every kept class is a sorted list of rows
 #[derive(Clone, Debug)]
-pub struct StrippedPartition(Vec<HashSet<usize>>);
+pub struct StrippedPartition {
+    clusters: Vec<Vec<u32>>,
+    row_num: usize
+}
 
 impl StrippedPartition {
     pub fn new(num: usize) -> StrippedPartition {
-        let set: HashSet<usize> = (0..num).collect();
-        StrippedPartition(vec![set])
+        let clusters = if num > 1 { vec![(0..num as u32).collect()] } else { Vec::new() };
+        StrippedPartition { clusters, row_num: num }
+    }
+
+    pub fn from_clusters(clusters: Vec<Vec<u32>>, row_num: usize) -> StrippedPartition {
+        let mut clusters: Vec<Vec<u32>> = clusters.into_iter().filter(|c| c.len() > 1).collect();
+        clusters.iter_mut().for_each(|c| c.sort_unstable());
+        StrippedPartition { clusters, row_num }
+    }
+
+    pub fn clusters(&self) -> &Vec<Vec<u32>> {
+        &self.clusters
+    }
+
+    pub fn row_num(&self) -> usize {
+        self.row_num
     }
 
     pub fn get_error(&self) -> i64 {
-        let element_cnt: usize = self.0.iter().map(|x| x.len()).sum();
-        // println!("Error is {}", (element_cnt as i64 ) - (self.0.len() as i64));
-        (element_cnt as i64 ) - (self.0.len() as i64)
+        let element_cnt: usize = self.clusters.iter().map(|x| x.len()).sum();
+        (element_cnt as i64 ) - (self.clusters.len() as i64)
+    }
+
+    // every row is in its own class, so the attributes form a key
+    pub fn is_unique(&self) -> bool {
+        self.clusters.is_empty()
+    }
+
+    // g3 error of X -> A, where self is the partition of X and refined is the partition of XA
+    pub fn g3_error(&self, refined: &StrippedPartition, num_rows: usize) -> f64 {
+        if num_rows == 0 {
+            return 0.0;
+        }
+
+        let mut class_size = vec![0usize; self.row_num.max(refined.row_num)];
+        for eq_class in refined.clusters.iter() {
+            class_size[eq_class[0] as usize] = eq_class.len();
+        }
+
+        let mut removed = 0;
+        for eq_class in self.clusters.iter() {
+            let mut max_size = 1;
+            for entry in eq_class {
+                max_size = max_size.max(class_size[*entry as usize]);
+            }
+            removed += eq_class.len() - max_size;
+        }
+
+        (removed as f64) / (num_rows as f64)
     }
 }
 
-impl<'a> Mul for &'a StrippedPartition {
-    type Output = StrippedPartition;
+// the classic TANE product, the row-to-cluster array is sized to the table and reused between products
+pub struct ProbeTable {
+    probe: Vec<u32>,
+    buckets: Vec<Vec<u32>>
+}
 
-    fn mul(self, rhs: &'a StrippedPartition) -> Self::Output { 
-        let mut temp_partition = StrippedPartition(Vec::new());
-        let mut res = Vec::new();
-        let mut idx_check = HashMap::<usize, usize>::new();
+impl ProbeTable {
+    pub fn new(row_num: usize) -> ProbeTable {
+        ProbeTable { probe: vec![0; row_num], buckets: Vec::new() }
+    }
+
+    pub fn product(&mut self, lhs: &StrippedPartition, rhs: &StrippedPartition) -> StrippedPartition {
+        let row_num = lhs.row_num.max(rhs.row_num);
+        if self.probe.len() < row_num {
+            self.probe.resize(row_num, 0);
+        }
+        if self.buckets.len() < lhs.clusters.len() {
+            self.buckets.resize(lhs.clusters.len(), Vec::new());
+        }
 
-        for (index, eq_class) in self.0.iter().enumerate() {
-            temp_partition.0.push(HashSet::new());
+        // 0 marks a row stripped from lhs
+        for (index, eq_class) in lhs.clusters.iter().enumerate() {
             for entry in eq_class {
-                idx_check.insert(*entry, index);
+                self.probe[*entry as usize] = index as u32 + 1;
             }
         }
 
-        for eq_class in rhs.0.iter() {
+        let mut res = Vec::new();
+        for eq_class in rhs.clusters.iter() {
             for entry in eq_class {
-                if let Some(index) = idx_check.get(entry) {
-                    temp_partition.0.get_mut(*index).unwrap().insert(*entry);
+                let index = self.probe[*entry as usize];
+                if index != 0 {
+                    self.buckets[index as usize - 1].push(*entry);
                 }
             }
             for entry in eq_class {
-                if let Some(index) = idx_check.get(entry) {
-                    if let Some(eq_class) = temp_partition.0.get_mut(*index) {
-                        if eq_class.len() > 1 {
-                            res.push(eq_class.clone());
-                        }
+                let index = self.probe[*entry as usize];
+                if index != 0 {
+                    let bucket = &mut self.buckets[index as usize - 1];
+                    if bucket.len() > 1 {
+                        res.push(std::mem::take(bucket));
+                    } else {
+                        bucket.clear();
                     }
-                    temp_partition.0[*index] = HashSet::new();
                 }
             }
         }
-        StrippedPartition(res)
+
+        for eq_class in lhs.clusters.iter() {
+            for entry in eq_class {
+                self.probe[*entry as usize] = 0;
+            }
+        }
+
+        StrippedPartition { clusters: res, row_num }
+    }
+}
+
+impl<'a> Mul for &'a StrippedPartition {
+    type Output = StrippedPartition;
+
+    fn mul(self, rhs: &'a StrippedPartition) -> Self::Output {
+        ProbeTable::new(self.row_num.max(rhs.row_num)).product(self, rhs)
+    }
+}
+
+// the classes of one column in the order of its values, singletons included. the rank of a row is
+// the position of its class counted from 1, equal nulls sort first and distinct nulls are left out with rank 0
+#[derive(Clone, Debug)]
+pub struct SortedPartition {
+    classes: Vec<Vec<u32>>,
+    ranks: Vec<u32>
+}
+
+impl SortedPartition {
+    pub fn new(table: &Table, col: usize, column_type: ColumnType, null_model: NullModel) -> SortedPartition {
+        let column = &table.columns[col];
+        let dictionary = &column.dictionary;
+
+        let mut codes: Vec<u32> = (0..dictionary.len() as u32).filter(|code| !dictionary.is_null(*code)).collect();
+        codes.sort_by(|a, b| column_type.compare(dictionary.decode(*a), dictionary.decode(*b)).then(a.cmp(b)));
+
+        // every value is a class of its own, so the classes are the stripped partition tane sees.
+        // values comparing equal as the type, like 7 and 7.0, are adjacent and share a rank
+        let nulls = usize::from(null_model == NullModel::Equal);
+        let mut code_class = vec![if nulls == 1 { Some(0) } else { None }; dictionary.len()];
+        let mut class_ranks = vec![1; nulls];
+        for (i, code) in codes.iter().enumerate() {
+            let tied = i > 0 && column_type.compare(dictionary.decode(codes[i - 1]), dictionary.decode(*code)).is_eq();
+            let rank = class_ranks.last().map_or(1, |last| if tied { *last } else { last + 1 });
+            code_class[*code as usize] = Some(class_ranks.len());
+            class_ranks.push(rank);
+        }
+
+        let mut classes = vec![Vec::new(); class_ranks.len()];
+        for (row, code) in column.codes.iter().enumerate() {
+            if let Some(class) = code_class[*code as usize] {
+                classes[class].push(row as u32);
+            }
+        }
+
+        let mut ranks = vec![0; column.codes.len()];
+        for (class, rank) in classes.iter().zip(class_ranks) {
+            for row in class {
+                ranks[*row as usize] = rank;
+            }
+        }
+        // values of the dictionary missing from a sub table leave no class, the ranks only keep their order
+        classes.retain(|class| !class.is_empty());
+        SortedPartition { classes, ranks }
+    }
+
+    pub fn classes(&self) -> &Vec<Vec<u32>> {
+        &self.classes
+    }
+
+    // 0 for a null under null != null, the rows tied as the type have the same rank
+    pub fn rank(&self, row: usize) -> u32 {
+        self.ranks[row]
+    }
+
+    // the stripped partition of the same column
+    pub fn stripped(&self) -> StrippedPartition {
+        StrippedPartition::from_clusters(self.classes.clone(), self.ranks.len())
+    }
+}
+
+// how two missing values compare when the partitions are built
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NullModel {
+    // every null falls into one class, whatever token it was written as
+    #[default]
+    Equal,
+    // every null is a singleton class, so it is stripped
+    Distinct
+}
+
+impl NullModel {
+    pub fn from_name(name: &str) -> Option<NullModel> {
+        match name {
+            "equal" | "null=null" => Some(NullModel::Equal),
+            "distinct" | "null!=null" => Some(NullModel::Distinct),
+            _ => None
+        }
+    }
+}
+
+impl Display for NullModel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NullModel::Equal => write!(f, "null = null"),
+            NullModel::Distinct => write!(f, "null ≠ null")
+        }
     }
 }
 
@@ -58,41 +228,170 @@ impl<'a> Mul for &'a StrippedPartition {
 pub struct Partitions<'a>(pub HashMap<&'a Attribute, StrippedPartition>);
 
 impl<'a> Partitions<'a> {
-    pub fn new(table: &Table) -> Partitions {
-        let mut partition = HashMap::<&Attribute, StrippedPartition>::new();
+    pub fn new(table: &Table) -> Partitions<'_> {
+        Partitions::with_null_model(table, NullModel::Equal)
+    }
 
-        let mut cnt = 0;
-        for meta in table.attributes.iter() {
-            let mut entry_check = HashMap::<String, HashSet<usize>>::new();
+    pub fn with_null_model(table: &Table, null_model: NullModel) -> Partitions<'_> {
+        let mut partition = HashMap::<&Attribute, StrippedPartition>::new();
 
-            for (index, entry) in table.entries.iter().enumerate() {                
-                let val = entry.data.get(cnt).unwrap();
+        for (meta, column) in table.attributes.iter().zip(table.columns.iter()) {
+            let mut entry_check = vec![Vec::<u32>::new(); column.dictionary.len()];
+            let mut nulls = Vec::<u32>::new();
 
-                if let Some(indexs) = entry_check.get_mut(val) {
-                    indexs.insert(index);
+            for (index, code) in column.codes.iter().enumerate() {
+                if column.dictionary.is_null(*code) {
+                    nulls.push(index as u32);
                 } else {
-                    let mut indexs = HashSet::new();
-                    indexs.insert(index);
-                    entry_check.insert(val.clone(), indexs);
+                    entry_check[*code as usize].push(index as u32);
                 }
             }
-            partition.insert(meta, StrippedPartition(entry_check.into_iter()
-                .map(|tuple|  tuple.1)
-                .filter(|set| set.len() > 1).collect()));
 
-            cnt = cnt + 1;
+            if null_model == NullModel::Equal {
+                entry_check.push(nulls);
+            }
+            partition.insert(meta, StrippedPartition::from_clusters(entry_check, table.row_num()));
         }
 
         Partitions(partition)
     }
 }
 
+// the cluster of a row that is alone in its class
+pub const UNIQUE: u32 = u32::MAX;
+
+// the partitions of the columns in column order
+pub fn column_partitions(table: &Table, null_model: NullModel) -> Vec<StrippedPartition> {
+    let mut partitions = Partitions::with_null_model(table, null_model);
+    table.attributes.iter().map(|attri| partitions.0.remove(attri).unwrap()).collect()
+}
+
+// the cluster of every row in every column, the row-wise view of the partitions used to compare row pairs
+pub fn compressed_records(partitions: &[StrippedPartition], row_num: usize) -> Vec<Vec<u32>> {
+    let mut records = vec![vec![UNIQUE; partitions.len()]; row_num];
+    for (column, partition) in partitions.iter().enumerate() {
+        for (id, cluster) in partition.clusters().iter().enumerate() {
+            for row in cluster {
+                records[*row as usize][column] = id as u32;
+            }
+        }
+    }
+    records
+}
+
+// the columns on which two rows agree
+pub fn agree_set(records: &[Vec<u32>], r: usize, s: usize) -> BitSet {
+    records[r].iter().zip(records[s].iter()).enumerate()
+        .filter(|(_, (x, y))| **x != UNIQUE && x == y)
+        .map(|(c, _)| c)
+        .collect()
+}
+
 impl<'a> Display for Partitions<'a> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let mut res = String::new();
         for (key, value) in self.0.iter() {
-            res += format!("Attribute {}: {:?}\n", key.value, value.0).as_str();
+            res += format!("Attribute {}: {:?}\n", key.value, value.clusters).as_str();
         }
         write!(f, "{{{}}}", res)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{self, Lcg};
+    use std::collections::HashSet;
+
+    // the product on hash sets the probe table replaced
+    fn hash_product(lhs: &StrippedPartition, rhs: &StrippedPartition) -> Vec<Vec<u32>> {
+        let mut temp_partition: Vec<HashSet<u32>> = Vec::new();
+        let mut res = Vec::new();
+        let mut idx_check = HashMap::<u32, usize>::new();
+
+        for (index, eq_class) in lhs.clusters.iter().enumerate() {
+            temp_partition.push(HashSet::new());
+            for entry in eq_class {
+                idx_check.insert(*entry, index);
+            }
+        }
+
+        for eq_class in rhs.clusters.iter() {
+            for entry in eq_class {
+                if let Some(index) = idx_check.get(entry) {
+                    temp_partition[*index].insert(*entry);
+                }
+            }
+            for entry in eq_class {
+                if let Some(index) = idx_check.get(entry) {
+                    if temp_partition[*index].len() > 1 {
+                        res.push(temp_partition[*index].iter().cloned().collect());
+                    }
+                    temp_partition[*index] = HashSet::new();
+                }
+            }
+        }
+        normalized(res)
+    }
+
+    fn normalized(clusters: Vec<Vec<u32>>) -> Vec<Vec<u32>> {
+        let mut clusters: Vec<Vec<u32>> = clusters.into_iter().map(|mut c| { c.sort_unstable(); c }).collect();
+        clusters.sort();
+        clusters
+    }
+
+    #[test]
+    fn probe_product_matches_the_hash_product() {
+        // one probe table for every product, the way tane uses it
+        let mut probe = ProbeTable::new(0);
+        for seed in 0..50 {
+            let mut rng = Lcg::new(seed);
+            let (attri_num, row_num) = (2 + rng.next(4) as usize, rng.next(60) as usize);
+            let domains: Vec<u64> = (0..attri_num).map(|_| 1 + rng.next(8)).collect();
+            let table = testing::random_table(&mut rng, attri_num, row_num, |rng| domains.iter().map(|d| testing::value(rng.next(*d + 1))).collect());
+
+            for null_model in [NullModel::Equal, NullModel::Distinct] {
+                let mut partitions = column_partitions(&table, null_model);
+                partitions.push(StrippedPartition::new(row_num));
+                for lhs in partitions.iter() {
+                    for rhs in partitions.iter() {
+                        let product = probe.product(lhs, rhs);
+                        assert_eq!(product.row_num(), row_num);
+                        assert_eq!(normalized(product.clusters().clone()), hash_product(lhs, rhs), "seed {}", seed);
+                        assert!(product.clusters().iter().all(|c| c.len() > 1 && c.windows(2).all(|w| w[0] < w[1])));
+                        assert_eq!(normalized((lhs * rhs).clusters().clone()), hash_product(lhs, rhs));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn null_models_group_or_strip_the_nulls() {
+        let rows: Vec<Vec<String>> = ["a", "", "NA", "a", "b", ""].iter().map(|v| vec![v.to_string()]).collect();
+        let metadata = vec![Attribute::new(0, "x".to_string())];
+        let mut table = Table::with_null_tokens("t".to_string(), metadata, vec![String::new(), "NA".to_string()]);
+        rows.into_iter().for_each(|row| table.add_entry(row));
+
+        // the nulls of both tokens form one class
+        let equal = column_partitions(&table, NullModel::Equal);
+        assert_eq!(normalized(equal[0].clusters().clone()), vec![vec![0, 3], vec![1, 2, 5]]);
+        let distinct = column_partitions(&table, NullModel::Distinct);
+        assert_eq!(normalized(distinct[0].clusters().clone()), vec![vec![0, 3]]);
+
+        // the compressed records mark the stripped nulls as unique
+        let records = compressed_records(&distinct, table.row_num());
+        assert_eq!(records[1][0], UNIQUE);
+        assert!(agree_set(&records, 1, 5).is_empty());
+        assert!(agree_set(&compressed_records(&equal, table.row_num()), 1, 5).contains(0));
+    }
+
+    #[test]
+    fn product_of_the_textbook_partitions() {
+        let lhs = StrippedPartition::from_clusters(vec![vec![0, 1, 2, 3], vec![4, 5], vec![6]], 7);
+        let rhs = StrippedPartition::from_clusters(vec![vec![0, 2, 4], vec![1, 3, 5, 6]], 7);
+        assert_eq!(normalized((&lhs * &rhs).clusters().clone()), vec![vec![0, 2], vec![1, 3]]);
+        assert!((&lhs * &StrippedPartition::new(0)).is_unique());
+        assert_eq!((&StrippedPartition::new(7) * &lhs).clusters(), lhs.clusters());
+    }
+}
@@ -1,37 +1,70 @@
-
-use std::{rc::Rc, fmt::Display, collections::HashSet};
+use std::{rc::Rc, fmt::Display, collections::{HashMap, HashSet}};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug)]
 pub struct Table {
     pub table_name: String,
     pub attributes: Rc<Vec<Attribute>>,
-    pub entries: Vec<Entry>
+    pub columns: Vec<Column>,
+    // the values standing for missing data, see NullModel for how they are compared
+    null_tokens: HashSet<String>,
+    row_num: usize
 }
 
 impl Table {
     pub fn new(table_name: String, metadata: Vec<Attribute>) -> Table {
+        Table::with_null_tokens(table_name, metadata, Vec::new())
+    }
+
+    pub fn with_null_tokens(table_name: String, metadata: Vec<Attribute>, null_tokens: Vec<String>) -> Table {
+        let columns = metadata.iter().map(|_| Column::new()).collect();
         Table {
             table_name,
             attributes: Rc::new(metadata),
-            entries: Vec::new()
+            columns,
+            null_tokens: null_tokens.into_iter().collect(),
+            row_num: 0
         }
     }
 
     pub fn add_entry(&mut self, data: Vec<String>) {
-        let entry = Entry::new(Rc::clone(&self.attributes), data);
-        self.entries.push(entry);
+        for (column, value) in self.columns.iter_mut().zip(data) {
+            let null = self.null_tokens.contains(&value);
+            column.push(value, null);
+        }
+        self.row_num += 1;
+    }
+
+    pub fn row_num(&self) -> usize {
+        self.row_num
+    }
+
+    pub fn code(&self, row: usize, col: usize) -> u32 {
+        self.columns[col].codes[row]
+    }
+
+    pub fn value(&self, row: usize, col: usize) -> &str {
+        self.columns[col].value(row)
+    }
+
+    pub fn is_null(&self, row: usize, col: usize) -> bool {
+        self.columns[col].is_null(row)
+    }
+
+    pub fn row(&self, row: usize) -> Vec<&str> {
+        self.columns.iter().map(|column| column.value(row)).collect()
     }
 
     pub fn sub_table(&self, node: &HashSet<usize>) -> Table {
         let table_name = self.table_name.clone();
         let attributes = Rc::clone(&self.attributes);
-        let entries: Vec<_> = node.iter().map(|idx| {
-            let entry = self.entries.get(*idx).unwrap();
-            Entry { attr_ref: Rc::clone(&entry.attr_ref), data: entry.data.clone()}
+        let rows: Vec<usize> = node.iter().cloned().collect();
+        let columns = self.columns.iter().map(|column| Column {
+            codes: rows.iter().map(|idx| column.codes[*idx]).collect(),
+            dictionary: Rc::clone(&column.dictionary)
         }).collect();
 
-        Table { table_name, attributes, entries}
+        Table { table_name, attributes, columns, null_tokens: self.null_tokens.clone(), row_num: rows.len() }
     }
 }
 
@@ -43,8 +76,11 @@ impl Display for Table {
             res += format!("{:<15}", attri.value).as_str();
         }
         res += "\n";
-        for entry in self.entries.iter() {
-            res += format!("{}\n", entry).as_str();
+        for row in 0..self.row_num {
+            for item in self.row(row) {
+                res += format!("{:<15}", item).as_str();
+            }
+            res += "\n";
         }
         write!(f, "{}", res)
     }
@@ -68,35 +104,117 @@ impl Display for Attribute {
     }
 }
 
+// interns the values of one column into dense codes, the strings are only kept for display.
+// every string is stored once, the map and the values share it
+#[derive(Debug, Clone, Default)]
+pub struct Dictionary {
+    values: Vec<Rc<str>>,
+    codes: HashMap<Rc<str>, u32>,
+    nulls: Vec<bool>
+}
+
+impl Dictionary {
+    pub fn encode(&mut self, value: String) -> u32 {
+        if let Some(code) = self.codes.get(value.as_str()) {
+            return *code;
+        }
+        let code = self.values.len() as u32;
+        let value: Rc<str> = Rc::from(value);
+        self.codes.insert(Rc::clone(&value), code);
+        self.values.push(value);
+        self.nulls.push(false);
+        code
+    }
+
+    pub fn mark_null(&mut self, code: u32) {
+        self.nulls[code as usize] = true;
+    }
+
+    pub fn is_null(&self, code: u32) -> bool {
+        self.nulls[code as usize]
+    }
+
+    pub fn decode(&self, code: u32) -> &str {
+        &self.values[code as usize]
+    }
+
+    pub fn lookup(&self, value: &str) -> Option<u32> {
+        self.codes.get(value).cloned()
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+// column-major storage, sub tables share the dictionary of their parent
 #[derive(Debug)]
-pub struct Entry {
-    pub attr_ref: Rc<Vec<Attribute>>,
-    pub data: Vec<String>
+pub struct Column {
+    pub codes: Vec<u32>,
+    pub dictionary: Rc<Dictionary>
 }
 
-impl Entry {
-    pub fn new(attr_ref: Rc<Vec<Attribute>>, data: Vec<String>) -> Entry {
-        Entry { attr_ref, data}
+impl Column {
+    fn new() -> Column {
+        Column { codes: Vec::new(), dictionary: Rc::new(Dictionary::default()) }
     }
 
-    pub fn get_data_from_metadata(&self, metadata: &String) -> Option<&String> {
-        let mut cnt = 0;
-        for meta in self.attr_ref.iter() {
-            if meta.value == *metadata {
-                return Some(&self.data[cnt]);
-            }
-            cnt = cnt + 1;
+    fn push(&mut self, value: String, null: bool) {
+        let dictionary = Rc::make_mut(&mut self.dictionary);
+        let code = dictionary.encode(value);
+        if null {
+            dictionary.mark_null(code);
         }
+        self.codes.push(code);
+    }
 
-        None
+    pub fn value(&self, row: usize) -> &str {
+        self.dictionary.decode(self.codes[row])
+    }
+
+    pub fn is_null(&self, row: usize) -> bool {
+        self.dictionary.is_null(self.codes[row])
     }
 }
-impl Display for Entry {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut res = String::new();
-        for item in self.data.iter() {
-            res += format!("{:<15}", item).as_str();
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codes_are_dense_and_decode_back() {
+        let mut dictionary = Dictionary::default();
+        let codes: Vec<u32> = ["b", "a", "b", "", "a", "c"].iter().map(|v| dictionary.encode(v.to_string())).collect();
+        assert_eq!(codes, vec![0, 1, 0, 2, 1, 3]);
+        assert_eq!(dictionary.len(), 4);
+        assert_eq!((0..4).map(|code| dictionary.decode(code)).collect::<Vec<_>>(), vec!["b", "a", "", "c"]);
+        assert_eq!(dictionary.lookup("c"), Some(3));
+        assert_eq!(dictionary.lookup("d"), None);
+    }
+
+    #[test]
+    fn null_tokens_share_no_code() {
+        let metadata = vec![Attribute::new(0, "a".to_string()), Attribute::new(1, "b".to_string())];
+        let mut table = Table::with_null_tokens("t".to_string(), metadata, vec![String::new(), "NA".to_string()]);
+        for row in [["1", ""], ["NA", "x"], ["1", "NA"], ["", "x"]] {
+            table.add_entry(row.iter().map(|v| v.to_string()).collect());
         }
-        write!(f, "{}", res)
+
+        // every token keeps its own code and value, both are nulls
+        assert_eq!((0..4).map(|row| table.is_null(row, 0)).collect::<Vec<_>>(), vec![false, true, false, true]);
+        assert_eq!((0..4).map(|row| table.is_null(row, 1)).collect::<Vec<_>>(), vec![true, false, true, false]);
+        assert_ne!(table.code(1, 0), table.code(3, 0));
+        assert_eq!(table.row(1), vec!["NA", "x"]);
+        assert_eq!(table.code(0, 0), table.code(2, 0));
+
+        // sub tables share the dictionary
+        let sub = table.sub_table(&[2].into_iter().collect());
+        assert_eq!(sub.row(0), vec!["1", "NA"]);
+        assert!(sub.is_null(0, 1));
+        assert_eq!(sub.code(0, 0), table.code(0, 0));
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,108 @@
+use crate::frontend::table::Table;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+
+// how the values of a column are ordered. the type is the most specific one
+// every non null value of the column parses as, strings compare byte-wise
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColumnType {
+    Numeric,
+    Date,
+    String
+}
+
+impl ColumnType {
+    pub fn from_name(name: &str) -> Option<ColumnType> {
+        match name {
+            "numeric" | "number" => Some(ColumnType::Numeric),
+            "date" => Some(ColumnType::Date),
+            "string" | "text" => Some(ColumnType::String),
+            _ => None
+        }
+    }
+
+    pub fn infer(table: &Table, col: usize) -> ColumnType {
+        let dictionary = &table.columns[col].dictionary;
+        let mut values = (0..dictionary.len() as u32).filter(|code| !dictionary.is_null(*code)).map(|code| dictionary.decode(code));
+
+        if values.clone().all(|value| parse_number(value).is_some()) {
+            ColumnType::Numeric
+        } else if values.all(|value| parse_date(value).is_some()) {
+            ColumnType::Date
+        } else {
+            ColumnType::String
+        }
+    }
+
+    // values that do not parse as the type come after the ones that do
+    pub fn compare(&self, a: &str, b: &str) -> Ordering {
+        match self {
+            ColumnType::Numeric => compare_parsed(parse_number(a), parse_number(b), |x, y| x.total_cmp(y)),
+            ColumnType::Date => compare_parsed(parse_date(a), parse_date(b), |x, y| x.cmp(y)),
+            ColumnType::String => a.cmp(b)
+        }
+    }
+}
+
+impl Display for ColumnType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ColumnType::Numeric => write!(f, "numeric"),
+            ColumnType::Date => write!(f, "date"),
+            ColumnType::String => write!(f, "string")
+        }
+    }
+}
+
+fn compare_parsed<T>(a: Option<T>, b: Option<T>, cmp: impl Fn(&T, &T) -> Ordering) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => cmp(&a, &b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal
+    }
+}
+
+fn parse_number(value: &str) -> Option<f64> {
+    value.trim().parse::<f64>().ok().filter(|number| !number.is_nan())
+}
+
+// yyyy-mm-dd or yyyy/mm/dd, optionally followed by a time hh:mm[:ss[.fff]] after a space or a T,
+// as (year, month, day, milliseconds of the day)
+fn parse_date(value: &str) -> Option<(i32, u32, u32, u32)> {
+    let value = value.trim();
+    let (date, time) = match value.find([' ', 'T']) {
+        Some(index) => (&value[..index], Some(&value[index + 1..])),
+        None => (value, None)
+    };
+
+    let parts: Vec<&str> = date.split(['-', '/']).collect();
+    if parts.len() != 3 || parts[0].len() != 4 || parts.iter().any(|part| part.is_empty() || !part.chars().all(|c| c.is_ascii_digit())) {
+        return None;
+    }
+    let (year, month, day) = (parts[0].parse().ok()?, parts[1].parse().ok()?, parts[2].parse().ok()?);
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+
+    let millis = match time {
+        None => 0,
+        Some(time) => {
+            let parts: Vec<&str> = time.split(':').collect();
+            if parts.len() < 2 || parts.len() > 3 {
+                return None;
+            }
+            let hour: u32 = parts[0].parse().ok()?;
+            let minute: u32 = parts[1].parse().ok()?;
+            let second: f64 = if parts.len() == 3 { parts[2].parse().ok()? } else { 0.0 };
+            if hour > 23 || minute > 59 || !(0.0..61.0).contains(&second) {
+                return None;
+            }
+            (hour * 3600 + minute * 60) * 1000 + (second * 1000.0).round() as u32
+        }
+    };
+
+    Some((year, month, day, millis))
+}
@@ -0,0 +1,23 @@
+#![feature(iter_intersperse)]
+// the miners keep the notation of their papers, X -> A with the partitions spX
+#![allow(non_snake_case)]
+
+pub mod frontend;
+pub mod algorithm;
+pub mod dependency;
+
+#[cfg(test)]
+mod testing;
+
+pub use frontend::table::{Attribute, Table};
+pub use frontend::parser::{load_from_file, load_from_file_with, load_from_reader, CsvOptions, Encoding};
+pub use frontend::partition::NullModel;
+pub use algorithm::{Algorithm, MultiTableAlgorithm};
+pub use algorithm::tane::Tane;
+pub use algorithm::validate::Validator;
+pub use dependency::fd::{Attributes, FunctionalDependency, FDs};
+pub use dependency::result::{AlgorithmResult, Format};
+pub use dependency::validation::Validations;
+
+#[cfg(feature = "extension-module")]
+pub mod python;
@@ -1,144 +1,607 @@
-#![feature(iter_intersperse)]
 use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 
-use crate::dependency::analysis::{Action, Type, Output, analysis_cli};
-use crate::algorithm::cluster;
-use crate::algorithm::tane::Tane;
-use crate::frontend::parser;
-use crate::frontend::table::Table;
-use algorithm::Algorithm;
-use clap::{arg, command, value_parser, ArgAction, Command, ArgGroup, builder::ValueParser};
-use serde::{Deserialize,Serialize};
-
-mod frontend;
-mod algorithm;
-mod dependency;
+use meta_miner::dependency::analysis::{Action, AttributesSource, Source, Type, Output, analysis_cli, analysis_rows, collection_fds};
+use meta_miner::dependency::workspace::{Workspace, DEFAULT_DATASET, DEFAULT_WORKSPACE};
+use meta_miner::dependency::normalize::{self, NormalForm};
+use meta_miner::algorithm::cluster::{self, Linkage};
+use meta_miner::algorithm::cfd::CfdMiner;
+use meta_miner::algorithm::dc::DcMiner;
+use meta_miner::algorithm::fastfds::FastFds;
+use meta_miner::algorithm::fastod::Fastod;
+use meta_miner::algorithm::hyfd::Hyfd;
+use meta_miner::algorithm::ucc::Ucc;
+use meta_miner::algorithm::spider::Spider;
+use meta_miner::frontend::types::ColumnType;
+use meta_miner::{load_from_file_with, Algorithm, AlgorithmResult, CsvOptions, Encoding, FDs, Format, FunctionalDependency, MultiTableAlgorithm, NullModel, Table, Tane, Validator};
+use clap::{arg, command, value_parser, Arg, ArgAction, ArgGroup, ArgMatches};
 
 fn main() {
     let matches = command!()
+        .arg(arg!(-w --workspace [WORKSPACE] "The directory keeping the fd collections of the analysis")
+                .value_parser(value_parser!(PathBuf))
+                .default_value(DEFAULT_WORKSPACE)
+                .global(true))
+        .arg(arg!(--dataset [DATASET] "The dataset whose fd collections are used")
+                .value_parser(value_parser!(String))
+                .default_value(DEFAULT_DATASET)
+                .global(true))
         .subcommand(
             command!("analysis")
-                .arg(arg!(-a --add <ADD> "Add the fds")
-                        .action(ArgAction::SetTrue)
-                        .requires("type"))
-                .arg(arg!(-l --load <LOAD> "load from file")
-                        .action(ArgAction::SetTrue)
-                        .requires("type"))
-                .arg(arg!(-d --display <DISPLAY> "Display the analysis of all the new fds")
-                        .action(ArgAction::SetTrue)
-                        .requires("number")
-                        .requires("rate"))
-                .arg(arg!(-c --clear <CLEAR> "Clear the current fds")
-                        .action(ArgAction::SetTrue)
-                        .requires("type"))
-                .arg(arg!(-s --single <SINGLE> "Analysis single fd")
-                        .requires("number")
-                        .requires("rate")
-                        .action(ArgAction::SetTrue))
-                .arg(arg!(--current <CURRENT> "Current display")
-                        .action(ArgAction::SetTrue)
-                        .requires("type"))
-                .arg(arg!(--attributes <Attributes>)
-                        .action(ArgAction::SetTrue))
-                .group(ArgGroup::new("actions")
-                        .required(true)
-                        .multiple(false)
-                        .args(["add", "display", "clear", "single", "current", "attributes", "load"]))
-                
-                .arg(arg!(-t --type [TYPE] "fd type")
-                        .value_parser(["mined", "new", "attributes"])
-                        .default_value("mined"))
-                
-                .arg(arg!(-o  --output [OUTPUT])
-                        .value_parser(["std", "mdfile"])
-                        .default_value("std"))
-
-                .arg(arg!(-n --number [NUM]  "The number of attributes in the table")
-                        .value_parser(value_parser!(usize)))
-                .arg(arg!(-r --rate [RATE]  "The error rate")
-                        .value_parser(value_parser!(f64)))      
+                .subcommand_required(true)
+                .subcommand(command!("add")
+                        .about("Add fds like `0,2 -> 3` to a collection, `-` reads one fd per line from stdin")
+                        .arg(arg!(<FD> ... "The fds").value_parser(value_parser!(String)))
+                        .arg(fd_type_arg()))
+                .subcommand(command!("load")
+                        .about("Add the fds of a file to a collection, one fd per line")
+                        .arg(arg!(-f --file <FILE> "The file of fds, `-` for stdin").value_parser(value_parser!(String)).required(true))
+                        .arg(fd_type_arg()))
+                .subcommand(command!("single")
+                        .about("Analysis single fd against the mined fds")
+                        .arg(arg!(<FD> "The fd, `-` reads it from stdin").value_parser(value_parser!(String)))
+                        .args(analysis_args()))
+                .subcommand(command!("display")
+                        .about("Display the analysis of all the new fds")
+                        .args(analysis_args()))
+                .subcommand(command!("clear")
+                        .about("Clear a collection")
+                        .arg(fd_type_arg()))
+                .subcommand(command!("current")
+                        .about("Current display of a collection")
+                        .arg(fd_type_arg())
+                        .arg(output_arg()))
+                .subcommand(command!("attributes")
+                        .about("Set the attributes the fd indexes refer to")
+                        .arg(arg!([NAMES] ... "The attribute names in column order, `-` reads them from stdin, separated by commas")
+                                .value_parser(value_parser!(String)))
+                        .arg(arg!(--"from-csv" <CSV> "Take the names from the header row of a csv file")
+                                .value_parser(value_parser!(String))
+                                .conflicts_with("NAMES"))
+                        .group(ArgGroup::new("source")
+                                .required(true)
+                                .args(["NAMES", "from-csv"])))
         ).subcommand(
             command!("execute")
                 .arg(arg!(-i --input <INPUT> "Input files")
                     .value_parser(value_parser!(String))
-                    .action(ArgAction::Set))
+                    .action(ArgAction::Append))
+                .args(csv_args())
+                .arg(arg!(-a --algorithm [ALGORITHM] "The discovery algorithm")
+                    .value_parser(["tane", "hyfd", "fastfds", "cfd", "od", "dc", "ucc", "ind"])
+                    .default_value("tane"))
+                .arg(arg!(-e --error [ERROR] "The maximum error of the approximate fds (g3) and dcs (share of the violating row pairs)")
+                    .value_parser(value_parser!(f64))
+                    .default_value("0.0"))
+                .arg(arg!(-p --parallel "Mine each lattice level on all the cores")
+                    .action(ArgAction::SetTrue))
+                .arg(arg!(--normalize [NORMALIZE] "Propose a decomposition of the table from the mined fds")
+                    .value_parser(["bcnf", "3nf"]))
+                .arg(arg!(--ddl "Print the proposed relations as SQL")
+                    .action(ArgAction::SetTrue)
+                    .requires("normalize"))
+                .arg(arg!(-o --output [OUTPUT] "Save the results into the file")
+                    .value_parser(value_parser!(String)))
+                .arg(arg!(-f --format [FORMAT] "The format of the output file, guessed from its extension by default")
+                    .value_parser(["json", "csv", "md"]))
+                .arg(arg!(--"min-support" [ROWS] "The rows a cfd pattern has to match")
+                    .value_parser(value_parser!(usize))
+                    .default_value("10"))
+                .arg(arg!(--"min-confidence" [CONFIDENCE] "The share of the matching rows a cfd has to hold on")
+                    .value_parser(value_parser!(f64))
+                    .default_value("1.0"))
+                .arg(arg!(--"max-lhs" [SIZE] "The attributes of the lhs of the constant cfds")
+                    .value_parser(value_parser!(usize))
+                    .default_value("3"))
+                .arg(arg!(--"max-condition" [SIZE] "The constant attributes of the variable cfds, 0 for constant cfds only")
+                    .value_parser(value_parser!(usize))
+                    .default_value("1"))
+                .arg(arg!(--"max-arity" [ARITY] "The columns of an ind, unbounded by default")
+                    .value_parser(value_parser!(u64).range(1..)))
+                .arg(arg!(--types [TYPES] "The types the columns are compared as for the ods and dcs, like `date,numeric,string`, inferred by default")
+                    .value_parser(value_parser!(String)))
+                .arg(arg!(--"max-predicates" [SIZE] "The predicates of a dc")
+                    .value_parser(value_parser!(usize))
+                    .default_value("4"))
+                .arg(arg!(--linkage [LINKAGE] "The linkage of the row clustering")
+                    .value_parser(["ward", "average", "complete"])
+                    .default_value("ward"))
+                .arg(arg!(--clusters [CLUSTERS] "The number of row clusters to mine")
+                    .value_parser(value_parser!(usize))
+                    .default_value("5"))
+                .arg(arg!(--"no-cluster" "Mine the whole table without clustering the rows")
+                    .action(ArgAction::SetTrue))
                     
                 
+        ).subcommand(
+            command!("validate")
+                .about("Check fds like `zip -> city` on a table and show the rows violating them")
+                .arg(arg!(-i --input <INPUT> "The input file")
+                    .value_parser(value_parser!(String))
+                    .required(true))
+                .arg(arg!([FD] ... "The fds, `-` reads one fd per line from stdin")
+                    .value_parser(value_parser!(String)))
+                .arg(arg!(--fds <FILE> "A file of fds, one per line")
+                    .value_parser(value_parser!(String)))
+                .arg(arg!(--collection <COLLECTION> "Check the fds of a collection of the workspace")
+                    .value_parser(["mined", "new"]))
+                .group(ArgGroup::new("source")
+                    .required(true)
+                    .multiple(true)
+                    .args(["FD", "fds", "collection"]))
+                .args(csv_args())
+                .arg(arg!(--"max-groups" [NUM] "Show at most this many violating groups of each fd")
+                    .value_parser(value_parser!(usize))
+                    .default_value("20"))
+                .arg(arg!(-o --output [OUTPUT] "Save the results into the file")
+                    .value_parser(value_parser!(String)))
+                .arg(arg!(-f --format [FORMAT] "The format of the output file, guessed from its extension by default")
+                    .value_parser(["json", "csv", "md"]))
         ).get_matches();
     
     env_logger::init();
 
+    let workspace_dir = matches.get_one::<PathBuf>("workspace").unwrap();
+    let dataset = matches.get_one::<String>("dataset").unwrap();
+
     
     match matches.subcommand() {
         Some(("analysis", sub_cmd)) => {
-            let action = {
-                if sub_cmd.get_flag("add") {
-                    Action::Add
-                } else if sub_cmd.get_flag("load") {
-                    Action::Load
-                } else if sub_cmd.get_flag("display") {
-                    Action::Display
-                } else if sub_cmd.get_flag("clear") {
-                    Action::Clear
-                } else if sub_cmd.get_flag("single") {
-                    Action::Single
-                } else if sub_cmd.get_flag("attributes") {
-                    Action::Attributes
-                } else if sub_cmd.get_flag("current") {
-                    Action::Current
-                } 
-                else {
-                    Action::Nothing
-                }
+            let (name, args) = sub_cmd.subcommand().unwrap();
+
+            let source = |values: Vec<String>| -> Source {
+                if values.len() == 1 && values[0] == "-" { Source::Stdin } else { Source::Args(values) }
+            };
+            let strings = |id: &str| -> Vec<String> {
+                args.get_many::<String>(id).map(|values| values.cloned().collect()).unwrap_or_default()
             };
 
-            let fd_type = if let Some(matches) = sub_cmd.get_one::<String>("type") {
-                    match matches.as_str() {
-                        "mined" => Type::Mined,
-                        "new" => Type::New,
-                        "attributes" => Type::Attributes,
-                        _ => unreachable!()
-                    }
-                } else {
-                   unreachable!()
-                };
+            let action = match name {
+                "add" => Action::Add(source(strings("FD"))),
+                "load" => match args.get_one::<String>("file").unwrap().as_str() {
+                    "-" => Action::Load(Source::Stdin),
+                    file => Action::Load(Source::File(file.to_string()))
+                },
+                "single" => Action::Single(source(strings("FD"))),
+                "display" => Action::Display,
+                "clear" => Action::Clear,
+                "current" => Action::Current,
+                "attributes" => match args.get_one::<String>("from-csv") {
+                    Some(path) => Action::Attributes(AttributesSource::Csv(path.clone())),
+                    None if strings("NAMES") == ["-"] => Action::Attributes(AttributesSource::Stdin),
+                    None => Action::Attributes(AttributesSource::Names(strings("NAMES")))
+                },
+                _ => unreachable!()
+            };
 
-            let n = if let Some(n) = sub_cmd.get_one::<usize>("number") {
-                *n
-            } else {
-                0
+            let fd_type = match args.try_get_one::<String>("type").ok().flatten().map(|t| t.as_str()) {
+                Some("new") => Type::New,
+                Some("attributes") => Type::Attributes,
+                _ => Type::Mined
+            };
+
+            let n = args.try_get_one::<usize>("number").ok().flatten().cloned().unwrap_or(0);
+            let r = args.try_get_one::<f64>("rate").ok().flatten().cloned().unwrap_or(0.0);
+
+            let output = match args.try_get_one::<String>("output").ok().flatten().map(|o| o.as_str()) {
+                Some("mdfile") => Output::Mdfile,
+                _ => Output::Std
             };
 
-            let r = if let Some(r) = sub_cmd.get_one::<f64>("rate") {
-                *r
+            // the mdfile goes into the workspace, so writing it takes the exclusive lock
+            let workspace = if action.is_read_only() && matches!(output, Output::Std) {
+                Workspace::open_read_only(workspace_dir, dataset)
             } else {
-                0.0
+                Workspace::open(workspace_dir, dataset)
             };
+            let workspace = workspace.unwrap_or_else(|e| {
+                log::error!("{}", e);
+                std::process::exit(1);
+            });
 
-            analysis_cli(action, fd_type, n, r);
+            match analysis_cli(&workspace, action, fd_type, n, r, output) {
+                Ok(report) => report.iter().for_each(|line| println!("{}", line)),
+                Err(e) => {
+                    eprintln!("error: {}", e);
+                    std::process::exit(1);
+                }
+            }
 
         }
         Some(("execute", sub_cmd)) => {
-            if let Some(path) = sub_cmd.get_one::<String>("input") {
-                let table = parser::load_from_file(path).unwrap();
+            let paths: Vec<&String> = sub_cmd.get_many::<String>("input").map(|paths| paths.collect()).unwrap_or_default();
+            let algorithm = sub_cmd.get_one::<String>("algorithm").unwrap().as_str();
+            let output = sub_cmd.get_one::<String>("output");
+            let format = output_format(sub_cmd, output);
+            let mut saved = 0;
+            let (options, null_model) = csv_options(sub_cmd);
+            if sub_cmd.get_flag("parallel") && !matches!(algorithm, "tane" | "fastfds" | "dc") {
+                log::warn!("{} runs on one core, --parallel is ignored", algorithm);
+            }
+            let normal_form = sub_cmd.get_one::<String>("normalize").map(|nf| NormalForm::from_name(nf).unwrap());
+            if normal_form.is_some() && !matches!(algorithm, "tane" | "hyfd" | "fastfds") {
+                eprintln!("error: --normalize needs the fds of tane, hyfd or fastfds, not the results of {}", algorithm);
+                std::process::exit(1);
+            }
+            // only the fds are analysed against the mined collection, and only if there is a workspace to read it from
+            let workspace = match algorithm {
+                "tane" | "hyfd" | "fastfds" => Workspace::open_existing(workspace_dir, dataset).unwrap_or_else(|e| {
+                    log::error!("{}", e);
+                    std::process::exit(1);
+                }),
+                _ => None
+            };
+
+            if algorithm == "ind" {
+                let tables: Vec<Table> = paths.iter().map(|path| load_or_exit(path, &options)).collect();
+                let mut spider = match sub_cmd.get_one::<u64>("max-arity") {
+                    Some(max_arity) => Spider::with_max_arity(*max_arity as usize),
+                    None => Spider::new()
+                };
+                let res = spider.execute_tables(&tables);
+                println!("{}", res);
+                finish(&res, output.cloned(), format);
+                return;
+            }
+
+            // the patterns of the cfds are the row subsets, so the tables are not clustered
+            if algorithm == "cfd" {
+                let multiple = paths.len() > 1;
+                for (index, path) in paths.into_iter().enumerate() {
+                    let table = load_or_exit(path, &options);
+                    let res = CfdMiner::new()
+                        .min_support(*sub_cmd.get_one::<usize>("min-support").unwrap())
+                        .min_confidence(*sub_cmd.get_one::<f64>("min-confidence").unwrap())
+                        .max_lhs(*sub_cmd.get_one::<usize>("max-lhs").unwrap())
+                        .max_condition(*sub_cmd.get_one::<usize>("max-condition").unwrap())
+                        .null_model(null_model)
+                        .execute(&table);
+                    print!("{}", res);
+                    let output = output.map(|output| if multiple { indexed_path(output, index) } else { output.clone() });
+                    finish(&res, output, format);
+                }
+                return;
+            }
+
+            // a key of a row cluster need not be a key of the table, so the uccs are not clustered either
+            if algorithm == "ucc" {
+                let multiple = paths.len() > 1;
+                for (index, path) in paths.into_iter().enumerate() {
+                    let table = load_or_exit(path, &options);
+                    let res = Ucc::new().null_model(null_model).execute(&table);
+                    print!("{}", res);
+                    let output = output.map(|output| if multiple { indexed_path(output, index) } else { output.clone() });
+                    finish(&res, output, format);
+                }
+                return;
+            }
+
+            // an od of a row cluster says nothing about the order of the whole table, so the ods are not clustered
+            if algorithm == "od" {
+                let error = *sub_cmd.get_one::<f64>("error").unwrap();
+                if error > 0.0 {
+                    log::warn!("The ods are mined exactly, the error {} is ignored", error);
+                }
+                let multiple = paths.len() > 1;
+                for (index, path) in paths.into_iter().enumerate() {
+                    let table = load_or_exit(path, &options);
+                    let mut fastod = Fastod::new().null_model(null_model);
+                    if let Some(column_types) = column_types(sub_cmd, &table, path) {
+                        fastod = fastod.column_types(column_types);
+                    }
+                    let res = fastod.execute(&table);
+                    print!("{}", res);
+                    let output = output.map(|output| if multiple { indexed_path(output, index) } else { output.clone() });
+                    finish(&res, output, format);
+                }
+                return;
+            }
+
+            // a dc compares every pair of rows, the pairs across two row clusters are as much evidence as the others
+            if algorithm == "dc" {
+                let multiple = paths.len() > 1;
+                for (index, path) in paths.into_iter().enumerate() {
+                    let table = load_or_exit(path, &options);
+                    let mut miner = DcMiner::new()
+                        .max_error(*sub_cmd.get_one::<f64>("error").unwrap())
+                        .max_predicates(*sub_cmd.get_one::<usize>("max-predicates").unwrap())
+                        .null_model(null_model);
+                    if let Some(column_types) = column_types(sub_cmd, &table, path) {
+                        miner = miner.column_types(column_types);
+                    }
+                    if sub_cmd.get_flag("parallel") {
+                        miner = miner.parallel();
+                    }
+                    let res = miner.execute(&table);
+                    print!("{}", res);
+                    let output = output.map(|output| if multiple { indexed_path(output, index) } else { output.clone() });
+                    finish(&res, output, format);
+                }
+                return;
+            }
+
+            let multiple = paths.len() > 1;
+            for path in paths {
+                let table = load_or_exit(path, &options);
                 println!("{}", table);
-                let nodes = cluster::clustering(&table);
+                // a decomposition is proposed for the whole table, not for a cluster of its rows
+                let nodes = if sub_cmd.get_flag("no-cluster") || normal_form.is_some() {
+                    vec![(0..table.row_num()).collect()]
+                } else {
+                    let linkage = Linkage::from_name(sub_cmd.get_one::<String>("linkage").unwrap()).unwrap();
+                    let cnt = *sub_cmd.get_one::<usize>("clusters").unwrap();
+                    row_clusters(&table, linkage, cnt)
+                };
+
+                let mut error = *sub_cmd.get_one::<f64>("error").unwrap();
+                if normal_form.is_some() && error > 0.0 {
+                    log::warn!("The decomposition needs exact fds, the error {} is ignored", error);
+                    error = 0.0;
+                }
+                if (algorithm == "hyfd" || algorithm == "fastfds") && error > 0.0 {
+                    log::warn!("{} only mines exact fds, the error {} is ignored", algorithm, error);
+                }
 
-                let mut tane = Tane::new();
+                let mut tane = Tane::with_error(error).null_model(null_model);
+                if sub_cmd.get_flag("parallel") {
+                    tane = tane.parallel();
+                }
+                let multiple = multiple || nodes.len() > 1;
                 for node in nodes {
                     let sub_table = table.sub_table(&node);
-                    let res = tane.execute(&sub_table);
                     println!("sub table of {}", sub_table);
-                    res.display();
+                    let output = output.map(|output| if multiple { indexed_path(output, saved) } else { output.clone() });
+                    saved += 1;
+                    let fds = match algorithm {
+                        "hyfd" => Hyfd::new().null_model(null_model).execute(&sub_table),
+                        "fastfds" if sub_cmd.get_flag("parallel") => FastFds::new().parallel().null_model(null_model).execute(&sub_table),
+                        "fastfds" => FastFds::new().null_model(null_model).execute(&sub_table),
+                        _ => tane.mine(&sub_table)
+                    };
+                    display_fds(workspace.as_ref(), &fds);
+                    if let Some(normal_form) = normal_form {
+                        let decomposition = match normal_form {
+                            NormalForm::BCNF => normalize::bcnf(&fds),
+                            NormalForm::ThirdNF => normalize::third_normal_form(&fds)
+                        };
+                        println!("{}", decomposition);
+                        if sub_cmd.get_flag("ddl") {
+                            println!("{}", decomposition.to_sql(&sub_table));
+                        }
+                    }
+                    finish(&fds, output, format);
                 }
                 // let res = tane.execute(table);
                 // res.display();
             }
         }
+        Some(("validate", sub_cmd)) => {
+            let (options, null_model) = csv_options(sub_cmd);
+            let table = load_or_exit(sub_cmd.get_one::<String>("input").unwrap(), &options);
+            let output = sub_cmd.get_one::<String>("output");
+            let format = output_format(sub_cmd, output);
+
+            let mut sources = Vec::new();
+            if let Some(values) = sub_cmd.get_many::<String>("FD") {
+                let values: Vec<String> = values.cloned().collect();
+                sources.push(if values == ["-"] { Source::Stdin } else { Source::Args(values) });
+            }
+            if let Some(path) = sub_cmd.get_one::<String>("fds") {
+                sources.push(Source::File(path.clone()));
+            }
+            if let Some(collection) = sub_cmd.get_one::<String>("collection") {
+                let workspace = match Workspace::open_existing(workspace_dir, dataset) {
+                    Ok(Some(workspace)) => workspace,
+                    Ok(None) => {
+                        eprintln!("error: there is no workspace {}", workspace_dir.join(dataset).display());
+                        std::process::exit(1);
+                    }
+                    Err(e) => {
+                        log::error!("{}", e);
+                        std::process::exit(1);
+                    }
+                };
+                let fd_type = if collection == "new" { Type::New } else { Type::Mined };
+                match collection_fds(&workspace, fd_type) {
+                    Ok(fds) => sources.push(Source::Args(fds)),
+                    Err(e) => {
+                        eprintln!("error: {}", e);
+                        std::process::exit(1);
+                    }
+                }
+            }
+
+            let mut fds = Vec::new();
+            let mut errors = Vec::new();
+            for source in sources.iter() {
+                let lines = source.lines().unwrap_or_else(|e| {
+                    eprintln!("error: {}", e);
+                    std::process::exit(1);
+                });
+                for (index, line) in lines {
+                    match FunctionalDependency::parse(&line, &table.attributes) {
+                        Ok(parsed) => fds.extend(parsed),
+                        Err(e) => errors.push(format!("line {}: {}", index + 1, e.report(&line)))
+                    }
+                }
+            }
+            if !errors.is_empty() {
+                eprintln!("error: {}", errors.join("\n"));
+                std::process::exit(1);
+            }
+
+            let max_groups = *sub_cmd.get_one::<usize>("max-groups").unwrap();
+            let res = Validator::new(fds).null_model(null_model).max_groups(max_groups).execute(&table);
+            print!("{}", res);
+            finish(&res, output.cloned(), format);
+        }
         _ => {
             log::warn!("Nothing to do")
         }
     }
     
     
-}
\ No newline at end of file
+}
+
+// the csv dialect and the null handling of the input files
+fn csv_args() -> Vec<Arg> {
+    vec![
+        arg!(--delimiter [DELIMITER] "The field delimiter of the csv files")
+            .value_parser(byte_arg)
+            .default_value(","),
+        arg!(--quote [QUOTE] "The quote char of the csv files")
+            .value_parser(byte_arg)
+            .default_value("\""),
+        arg!(--escape [ESCAPE] "The escape char of quotes, quotes are doubled by default")
+            .value_parser(byte_arg),
+        arg!(--comment [COMMENT] "Skip the lines starting with this char")
+            .value_parser(byte_arg),
+        arg!(--"no-header" "The csv files have no header row")
+            .action(ArgAction::SetTrue),
+        arg!(--encoding [ENCODING] "The encoding of the csv files")
+            .value_parser(["utf8", "latin1"])
+            .default_value("utf8"),
+        arg!(--limit [LIMIT] "Load at most this many rows of each file")
+            .value_parser(value_parser!(usize)),
+        arg!(--"null-tokens" [TOKENS] "Comma separated values loaded as nulls, the empty field by default")
+            .value_parser(value_parser!(String))
+            .value_delimiter(',')
+            .default_value(""),
+        arg!(--"null-model" [MODEL] "Whether two nulls agree on a column")
+            .value_parser(["equal", "distinct"])
+            .default_value("equal")
+    ]
+}
+
+fn csv_options(args: &ArgMatches) -> (CsvOptions, NullModel) {
+    let options = CsvOptions {
+        delimiter: *args.get_one::<u8>("delimiter").unwrap(),
+        quote: *args.get_one::<u8>("quote").unwrap(),
+        escape: args.get_one::<u8>("escape").cloned(),
+        comment: args.get_one::<u8>("comment").cloned(),
+        has_header: !args.get_flag("no-header"),
+        encoding: Encoding::from_name(args.get_one::<String>("encoding").unwrap()).unwrap(),
+        limit: args.get_one::<usize>("limit").cloned(),
+        null_tokens: args.get_many::<String>("null-tokens").map(|tokens| tokens.cloned().collect()).unwrap_or_default()
+    };
+    let null_model = NullModel::from_name(args.get_one::<String>("null-model").unwrap()).unwrap();
+    (options, null_model)
+}
+
+fn fd_type_arg() -> Arg {
+    arg!(-t --type [TYPE] "The collection")
+        .value_parser(["mined", "new", "attributes"])
+        .default_value("mined")
+}
+
+fn output_arg() -> Arg {
+    arg!(-o --output [OUTPUT] "Print the report or write it into analysis.md of the workspace")
+        .value_parser(["std", "mdfile"])
+        .default_value("std")
+}
+
+// the r-neighbourhood parameters of the analysis reports
+fn analysis_args() -> Vec<Arg> {
+    vec![
+        arg!(-n --number <NUM> "The number of attributes in the table")
+            .value_parser(value_parser!(usize))
+            .required(true),
+        arg!(-r --rate <RATE> "The error rate")
+            .value_parser(value_parser!(f64))
+            .required(true),
+        output_arg()
+    ]
+}
+
+// the --format, or the one of the output extension
+fn output_format(args: &ArgMatches, output: Option<&String>) -> Format {
+    match (args.get_one::<String>("format"), output) {
+        (Some(format), _) => Format::from_name(format).unwrap(),
+        (None, Some(output)) => Format::from_path(output).unwrap_or_else(|e| {
+            eprintln!("error: {}", e);
+            std::process::exit(1);
+        }),
+        (None, None) => Format::Json
+    }
+}
+
+// the table of an input file, a file that can not be read or parsed ends the run
+fn load_or_exit(path: &str, options: &CsvOptions) -> Table {
+    load_from_file_with(path, options).unwrap_or_else(|e| {
+        log::error!("Can not load {}: {}", path, e);
+        std::process::exit(1);
+    })
+}
+
+// the --types of the columns, one per column of the table
+fn column_types(args: &ArgMatches, table: &Table, path: &str) -> Option<Vec<ColumnType>> {
+    args.get_one::<String>("types").map(|types| {
+        let types: Vec<ColumnType> = types.split(',').map(|t| ColumnType::from_name(t.trim()).unwrap_or_else(|| {
+            log::error!("Unknown column type {}, expected numeric, date or string", t);
+            std::process::exit(1);
+        })).collect();
+        if types.len() != table.attributes.len() {
+            log::error!("{} column types given for the {} columns of {}", types.len(), table.attributes.len(), path);
+            std::process::exit(1);
+        }
+        types
+    })
+}
+
+// the statistics of the run, then the result is saved when an output is given
+fn finish<R: AlgorithmResult>(res: &R, output: Option<String>, format: Format) {
+    print!("{}", res.statistics());
+    if let Some(output) = output {
+        if let Err(e) = res.save_as_file(&output, format) {
+            log::error!("Can not write {}: {}", output, e);
+            std::process::exit(1);
+        }
+    }
+}
+
+// the fds followed by their r-neighbourhood analysis against the mined set
+fn display_fds(workspace: Option<&Workspace>, fds: &FDs) {
+    let rows = analysis_rows(workspace, fds).unwrap_or_else(|e| {
+        eprintln!("error: {}", e);
+        std::process::exit(1);
+    });
+    println!("We have functional dependency set of the table {} ({}):", fds.table_name(), fds.null_model());
+    for (fd, row) in fds.iter().zip(rows) {
+        println!("{}", row);
+        if fd.error() > 0.0 {
+            println!("g3 error: {:.4}", fd.error());
+        }
+    }
+}
+
+// the python feature hands the rows to python/cluster.py, which picks its own linkage and clusters
+#[cfg(feature = "python")]
+fn row_clusters(table: &Table, _linkage: Linkage, _cnt: usize) -> Vec<HashSet<usize>> {
+    log::info!("Clustering the rows in python, --linkage and --clusters are ignored");
+    cluster::python_clustering(table)
+}
+
+#[cfg(not(feature = "python"))]
+fn row_clusters(table: &Table, linkage: Linkage, cnt: usize) -> Vec<HashSet<usize>> {
+    cluster::clustering_with(table, linkage, cnt)
+}
+
+// results.json -> results_3.json, so the results of every sub table are kept
+fn indexed_path(path: &str, index: usize) -> String {
+    let path = Path::new(path);
+    let stem = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+    let name = match path.extension() {
+        Some(ext) => format!("{}_{}.{}", stem, index, ext.to_string_lossy()),
+        None => format!("{}_{}", stem, index)
+    };
+    path.with_file_name(name).to_string_lossy().to_string()
+}
+
+// a single byte char of the csv dialect, `\t` and `tab` stand for the tab
+fn byte_arg(s: &str) -> Result<u8, String> {
+    match s {
+        "\\t" | "tab" => Ok(b'\t'),
+        _ if s.len() == 1 => Ok(s.as_bytes()[0]),
+        _ => Err(format!("`{}` is not a single byte char", s))
+    }
+}
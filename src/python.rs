@@ -0,0 +1,153 @@
+use crate::algorithm::Algorithm;
+use crate::algorithm::fastfds::FastFds;
+use crate::algorithm::hyfd::Hyfd;
+use crate::algorithm::tane::Tane;
+use crate::dependency::fd::FDs;
+use crate::frontend::partition::NullModel;
+use crate::frontend::table::{Attribute, Table};
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyFloat};
+
+// None and NaN cells are loaded as this token, which is always a null token
+const NULL_CELL: &str = "";
+
+#[pyclass(name = "FD")]
+#[derive(Clone)]
+pub struct PyFD {
+    #[pyo3(get)]
+    lhs: Vec<String>,
+    #[pyo3(get)]
+    rhs: String,
+    #[pyo3(get)]
+    lhs_indices: Vec<usize>,
+    #[pyo3(get)]
+    rhs_index: usize,
+    // the g3 error, 0 for an exact fd
+    #[pyo3(get)]
+    error: f64,
+    #[pyo3(get)]
+    null_model: String
+}
+
+#[pymethods]
+impl PyFD {
+    #[getter]
+    fn is_exact(&self) -> bool {
+        self.error == 0.0
+    }
+
+    fn __repr__(&self) -> String {
+        format!("FD({{{}}} -> {}, error={})", self.lhs.join(", "), self.rhs, self.error)
+    }
+}
+
+fn to_py_fds(fds: &FDs) -> Vec<PyFD> {
+    fds.iter().map(|fd| {
+        let (determinant, dependant) = fd.disintegrate();
+        PyFD {
+            lhs: determinant.iter().map(|a| a.value.clone()).collect(),
+            rhs: dependant[0].value.clone(),
+            lhs_indices: determinant.iter().map(|a| a.rank).collect(),
+            rhs_index: dependant[0].rank,
+            error: fd.error(),
+            null_model: fds.null_model().to_string()
+        }
+    }).collect()
+}
+
+fn cell(value: &PyAny) -> PyResult<String> {
+    if value.is_none() {
+        return Ok(NULL_CELL.to_string());
+    }
+    if let Ok(float) = value.downcast::<PyFloat>() {
+        if float.value().is_nan() {
+            return Ok(NULL_CELL.to_string());
+        }
+    }
+    Ok(value.str()?.to_string())
+}
+
+// a mapping or a pandas-like frame gives its columns, anything else is iterated as rows
+fn to_table(data: &PyAny, columns: Option<Vec<String>>, null_tokens: Vec<String>) -> PyResult<Table> {
+    let by_column = data.is_instance_of::<PyDict>() || data.hasattr("columns")?;
+
+    let (names, cells): (Vec<String>, Vec<Vec<String>>) = if by_column {
+        let names: Vec<String> = match columns {
+            Some(columns) => columns,
+            None if data.is_instance_of::<PyDict>() => data.downcast::<PyDict>()?.keys().iter().map(|k| k.str().map(|s| s.to_string())).collect::<PyResult<_>>()?,
+            None => data.getattr("columns")?.iter()?.map(|k| k?.str().map(|s| s.to_string())).collect::<PyResult<_>>()?
+        };
+        let columns: Vec<Vec<String>> = names.iter().map(|name| {
+            data.get_item(name.as_str())?.iter()?.map(|value| cell(value?)).collect::<PyResult<Vec<_>>>()
+        }).collect::<PyResult<_>>()?;
+
+        let row_num = columns.first().map(|c| c.len()).unwrap_or(0);
+        if columns.iter().any(|c| c.len() != row_num) {
+            return Err(PyValueError::new_err("all the columns must have the same length"));
+        }
+        (names, (0..row_num).map(|row| columns.iter().map(|c| c[row].clone()).collect()).collect())
+    } else {
+        let rows: Vec<Vec<String>> = data.iter()?.map(|row| {
+            row?.iter()?.map(|value| cell(value?)).collect::<PyResult<Vec<_>>>()
+        }).collect::<PyResult<_>>()?;
+
+        let width = rows.first().map(|r| r.len()).unwrap_or(0);
+        if rows.iter().any(|r| r.len() != width) {
+            return Err(PyValueError::new_err("all the rows must have the same length"));
+        }
+        let names = columns.unwrap_or_else(|| (0..width).map(|i| format!("column_{}", i)).collect());
+        if names.len() != width {
+            return Err(PyValueError::new_err(format!("{} column names for rows of {} values", names.len(), width)));
+        }
+        (names, rows)
+    };
+
+    let metadata = names.into_iter().enumerate().map(|(rank, name)| Attribute::new(rank, name)).collect();
+    let mut null_tokens = null_tokens;
+    null_tokens.push(NULL_CELL.to_string());
+    let mut table = Table::with_null_tokens("data".to_string(), metadata, null_tokens);
+    for row in cells {
+        table.add_entry(row);
+    }
+    Ok(table)
+}
+
+#[pyfunction]
+#[pyo3(signature = (data, algorithm = "tane", columns = None, error = 0.0, null_model = "equal", null_tokens = Vec::new(), parallel = false))]
+fn discover_fds(data: &PyAny, algorithm: &str, columns: Option<Vec<String>>, error: f64,
+                null_model: &str, null_tokens: Vec<String>, parallel: bool) -> PyResult<Vec<PyFD>> {
+    let null_model = NullModel::from_name(null_model)
+        .ok_or_else(|| PyValueError::new_err(format!("unknown null model `{}`, expected equal or distinct", null_model)))?;
+    let table = to_table(data, columns, null_tokens)?;
+
+    let fds = match algorithm {
+        "tane" => {
+            let mut tane = Tane::with_error(error).null_model(null_model);
+            if parallel {
+                tane = tane.parallel();
+            }
+            tane.mine(&table)
+        }
+        "hyfd" | "fastfds" if error > 0.0 => return Err(PyValueError::new_err(format!("{} only mines exact fds", algorithm))),
+        "hyfd" => Hyfd::new().null_model(null_model).execute(&table),
+        "fastfds" => {
+            let mut fastfds = FastFds::new().null_model(null_model);
+            if parallel {
+                fastfds = fastfds.parallel();
+            }
+            fastfds.execute(&table)
+        }
+        _ => return Err(PyValueError::new_err(format!("unknown fd algorithm `{}`", algorithm)))
+    };
+
+    Ok(to_py_fds(&fds))
+}
+
+#[pymodule]
+fn meta_miner(_py: Python, m: &PyModule) -> PyResult<()> {
+    m.add_class::<PyFD>()?;
+    m.add_function(wrap_pyfunction!(discover_fds, m)?)?;
+    Ok(())
+}
@@ -0,0 +1,51 @@
+// fixtures shared by the unit tests of the miners
+use crate::frontend::table::{Attribute, Table};
+use crate::dependency::fd::FDs;
+
+// a linear congruential generator, the random tables are the same on every run
+pub struct Lcg(u64);
+
+impl Lcg {
+    pub fn new(seed: u64) -> Lcg {
+        Lcg(seed)
+    }
+
+    // a number below bound
+    pub fn next(&mut self, bound: u64) -> u64 {
+        self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        (self.0 >> 33) % bound
+    }
+}
+
+// the empty string is the null token
+pub fn table(name: &str, names: &[&str], rows: &[Vec<String>]) -> Table {
+    let metadata = names.iter().enumerate().map(|(rank, name)| Attribute::new(rank, name.to_string())).collect();
+    let mut table = Table::with_null_tokens(name.to_string(), metadata, vec![String::new()]);
+    for row in rows {
+        table.add_entry(row.clone());
+    }
+    table
+}
+
+// the columns are named c0, c1, ..., every call of row gives the values of one row
+pub fn random_table(rng: &mut Lcg, attri_num: usize, row_num: usize, mut row: impl FnMut(&mut Lcg) -> Vec<String>) -> Table {
+    let names: Vec<String> = (0..attri_num).map(|c| format!("c{}", c)).collect();
+    let names: Vec<&str> = names.iter().map(|n| n.as_str()).collect();
+    let rows: Vec<Vec<String>> = (0..row_num).map(|_| row(rng)).collect();
+    table("random", &names, &rows)
+}
+
+// the value v of a domain, 0 is the null
+pub fn value(v: u64) -> String {
+    match v {
+        0 => String::new(),
+        v => v.to_string()
+    }
+}
+
+// the fds as sorted (lhs ranks, rhs rank) pairs, to compare the results of two miners
+pub fn fd_set(fds: &FDs) -> Vec<(Vec<usize>, usize)> {
+    let mut set: Vec<_> = fds.iter().map(|fd| (fd.determinant_set().iter().collect(), fd.dependant_rank())).collect();
+    set.sort();
+    set
+}